@@ -1,5 +1,7 @@
-use crate::types::{
-    next_id, Anchor, AppState, Bounds, Element, Id, Message, Renderer, Scale, Size,
+use crate::{
+    children::{ChildEntry, KeyedChildren},
+    focus::Focusable,
+    types::{next_id, Anchor, AppState, Bounds, Element, Id, Position, Renderer, Scale, Size},
 };
 
 pub struct AnchorContainerProps<E> {
@@ -22,10 +24,19 @@ pub struct AnchorEntry<E> {
     pub child: E,
 }
 
+impl<E: Element> ChildEntry for AnchorEntry<E> {
+    fn child_id(&self) -> Id {
+        self.child.id()
+    }
+}
+
 pub struct AnchorContainer<E> {
     pub id: Id,
     pub bounds: Bounds,
     pub entries: Vec<AnchorEntry<E>>,
+
+    // Children changed since the last layout.
+    dirty: bool,
 }
 
 impl<E: Element> AnchorContainer<E> {
@@ -38,17 +49,28 @@ impl<E: Element> AnchorContainer<E> {
                 size: Size { w: 0., h: 0. },
             },
             entries: props.entries,
+            dirty: false,
         }
     }
 }
 
-impl<E: Element> Element for AnchorContainer<E> {
-    fn handle(&mut self, msg: &Message) {
-        for entry in &mut self.entries {
-            entry.child.handle(msg);
-        }
+impl<E: Element> KeyedChildren for AnchorContainer<E> {
+    type Entry = AnchorEntry<E>;
+
+    fn entries(&self) -> &[AnchorEntry<E>] {
+        &self.entries
     }
 
+    fn entries_mut(&mut self) -> &mut Vec<AnchorEntry<E>> {
+        &mut self.entries
+    }
+
+    fn children_changed(&mut self) {
+        self.dirty = true;
+    }
+}
+
+impl<E: Element> Element for AnchorContainer<E> {
     fn update(&mut self, state: &AppState) {
         for entry in &mut self.entries {
             entry.child.update(state);
@@ -62,6 +84,7 @@ impl<E: Element> Element for AnchorContainer<E> {
     }
 
     fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
         self.bounds = bounds;
         for entry in &mut self.entries {
             let size = Size {
@@ -82,6 +105,32 @@ impl<E: Element> Element for AnchorContainer<E> {
         self.bounds
     }
 
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.dirty || self.entries.iter().any(|entry| entry.child.needs_layout())
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        self.entries
+            .iter()
+            .map(|entry| &entry.child as &dyn Element)
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        self.entries
+            .iter_mut()
+            .map(|entry| &mut entry.child as &mut dyn Element)
+            .collect()
+    }
+
     fn min_size(&self) -> Size {
         todo!()
     }
@@ -96,4 +145,16 @@ impl<E: Element> Element for AnchorContainer<E> {
             entry.child.write_all_bounds(v)
         }
     }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.child.can_scroll(position, wheel))
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        for entry in &self.entries {
+            entry.child.write_focusable(v)
+        }
+    }
 }
@@ -0,0 +1,192 @@
+use crate::{
+    focus::FocusManager,
+    input::InputTracker,
+    inspector::Inspector,
+    router::Router,
+    types::{
+        draw_with_overlays, hit_test_with_overlays, AppState, Bounds, Element, EventObserver,
+        MacroquadRenderer, Renderer, Size, UIContext,
+    },
+};
+use macroquad::prelude::*;
+
+/// Owns a tree of elements together with everything needed to run it, and runs its frames.
+pub struct App<E, R = MacroquadRenderer> {
+    pub root: E,
+    pub ctx: UIContext,
+    pub renderer: R,
+    pub router: Router,
+    pub focus: FocusManager,
+    pub observer: EventObserver,
+    pub inspector: Inspector,
+    pub background: Color,
+
+    // Size the root was last laid out with.
+    screen: Size,
+}
+
+impl<E: Element, R: Renderer> App<E, R> {
+    pub fn new(root: E, ctx: UIContext, renderer: R) -> Self {
+        let mut router = Router::new();
+        router.mount(&root);
+        App {
+            focus: FocusManager::new(&ctx),
+            inspector: Inspector::new(&ctx),
+            root,
+            ctx,
+            renderer,
+            router,
+            observer: EventObserver::new(),
+            background: WHITE,
+            screen: Size { w: 0., h: 0. },
+        }
+    }
+
+    /// Runs a single frame without touching the window: lays the tree out if needed, updates
+    /// it with `state`, calls `update`, delivers every message sent in the meantime and draws.
+    /// While the inspector has frozen the UI only drawing happens.
+    pub fn step(
+        &mut self,
+        screen: Size,
+        mut state: AppState,
+        update: &mut impl FnMut(&mut Self, &AppState),
+    ) {
+        if self.inspector.update(&state) {
+            if screen != self.screen || self.root.needs_layout() {
+                self.root.set_bounds(Bounds {
+                    x: 0.,
+                    y: 0.,
+                    size: screen,
+                });
+                self.screen = screen;
+            }
+
+            state.hovered = hit_test_with_overlays(&self.root, state.mouse_position);
+            self.root.update(&state);
+            self.focus.update(&self.root, &state);
+            update(self, &state);
+            self.dispatch();
+        }
+
+        draw_with_overlays(&self.root, &self.renderer);
+        self.focus.draw(&self.root, &self.renderer);
+        self.inspector
+            .draw(&self.root, &self.renderer, state.mouse_position);
+    }
+
+    /// Delivers queued messages until there are none left, including the ones sent while
+    /// handling earlier ones.
+    pub fn dispatch(&mut self) {
+        while let Ok(msg) = self.ctx.rx.try_recv() {
+            if msg.is_broadcast() {
+                Router::broadcast(&mut self.root, &msg);
+            } else {
+                self.router.dispatch(&mut self.root, &msg);
+            }
+            self.focus.handle(&msg);
+            self.observer.handle(msg);
+        }
+    }
+}
+
+impl<E: Element> App<E> {
+    /// Runs the app in the macroquad window forever, calling `update` once per frame after
+    /// the elements were updated.
+    pub async fn run(mut self, mut update: impl FnMut(&mut Self, &AppState)) {
+        let mut input = InputTracker::new();
+        loop {
+            clear_background(self.background);
+            input.poll_macroquad();
+            let state = input.frame(get_frame_time());
+            let screen = Size {
+                w: screen_width(),
+                h: screen_height(),
+            };
+            self.step(screen, state, &mut update);
+            next_frame().await
+        }
+    }
+}
+
+#[test]
+pub fn test_app_step() {
+    use crate::button::{Button, ButtonMessage, ButtonProps};
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+    use crate::types::{KeyState, MessageData, Position};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let button = Button::new(ButtonProps::default(), &ctx);
+    let mut app = App::new(button, ctx, RecordingRenderer::new());
+    let observed = app.observer.observe(app.root.id);
+    let screen = Size { w: 100., h: 40. };
+
+    let mut frames = 0;
+    let mut count_frames = |_: &mut App<Button, RecordingRenderer>, _: &AppState| frames += 1;
+    app.step(screen, AppState::default(), &mut count_frames);
+    assert_eq!(app.root.outer_bounds.size, screen);
+    assert!(!app.renderer.take().is_empty());
+
+    app.step(
+        screen,
+        AppState {
+            mouse_position: Position { x: 90., y: 20. },
+            left_click: KeyState::Pressed,
+            ..Default::default()
+        },
+        &mut count_frames,
+    );
+    assert_eq!(frames, 2);
+    assert!(observed
+        .drain()
+        .any(|data| matches!(data, MessageData::Button(ButtonMessage::Click))));
+}
+
+#[test]
+pub fn test_app_broadcast() {
+    use crate::recording_renderer::RecordingRenderer;
+    use crate::types::{next_id, Id, Message, MessageData, Space};
+
+    // Counts the messages it is offered.
+    struct Counter(Id, usize);
+    impl Element for Counter {
+        fn update(&mut self, _state: &AppState) {}
+        fn handle(&mut self, _msg: &Message) {
+            self.1 += 1;
+        }
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, _bounds: Bounds) {}
+        fn bounds(&self) -> Bounds {
+            Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            }
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 0., h: 0. }
+        }
+        fn space(&self) -> Space {
+            Space::Fill
+        }
+        fn id(&self) -> Id {
+            self.0
+        }
+    }
+
+    let ctx = UIContext::new();
+    let mut app = App::new(Counter(next_id(), 0), ctx, RecordingRenderer::new());
+    let send = |app: &App<Counter, RecordingRenderer>, msg| app.ctx.tx.send(msg).unwrap();
+
+    // Messages for elements that aren't in the tree are dropped, broadcasts reach everyone.
+    send(
+        &app,
+        Message {
+            target: next_id(),
+            data: MessageData::Null,
+        },
+    );
+    send(&app, Message::broadcast(MessageData::Null));
+    app.dispatch();
+    assert_eq!(app.root.1, 1);
+}
@@ -1,10 +1,15 @@
-use crate::types::{
-    next_id, ActionState, Anchor, AnchorX, AnchorY, AppState, Bounds, Element, Id, KeyState,
-    Message, MessageData, PreserveRatio, Renderer, Size, Space, UIContext,
+use crate::{
+    callback::{AppHandle, Callback},
+    focus::{FocusMessage, Focusable},
+    types::{
+        next_id, ActionState, Anchor, AnchorX, AnchorY, AppState, Bounds, Element, Id, KeyState,
+        Message, MessageData, PreserveRatio, Renderer, Size, Space, TextMeasurer, UIContext,
+        DEFAULT_FONT_SIZE,
+    },
 };
 use assert_float_eq::{afe_is_f32_near, afe_near_error_msg, assert_f32_near};
 use macroquad::prelude::*;
-use std::ops::RangeInclusive;
+use std::{ops::RangeInclusive, rc::Rc};
 
 fn bounce(x: f32) -> f32 {
     let x = x.clamp(0., 1.);
@@ -29,6 +34,8 @@ pub struct ButtonProps {
     pub image: Option<u32>,
     pub color: Color,
     pub preserve_ratio: PreserveRatio,
+    pub on_click: Option<Callback>,
+    pub on_hover: Option<Callback<ActionState>>,
 }
 
 impl Default for ButtonProps {
@@ -44,6 +51,8 @@ impl Default for ButtonProps {
             image: None,
             color: WHITE,
             preserve_ratio: PreserveRatio::None,
+            on_click: None,
+            on_hover: None,
         }
     }
 }
@@ -53,11 +62,13 @@ impl Clone for ButtonProps {
         Self {
             id: next_id(),
             text: self.text.clone(),
-            space: self.space.clone(),
-            anchor: self.anchor.clone(),
-            image: self.image.clone(),
-            color: self.color.clone(),
+            space: self.space,
+            anchor: self.anchor,
+            image: self.image,
+            color: self.color,
             preserve_ratio: self.preserve_ratio.clone(),
+            on_click: self.on_click.clone(),
+            on_hover: self.on_hover.clone(),
         }
     }
 }
@@ -73,7 +84,14 @@ pub struct Button {
     color: Color,
     preserve_ratio: PreserveRatio,
 
+    on_click: Option<Callback>,
+    on_hover: Option<Callback<ActionState>>,
+
     tx: flume::Sender<Message>,
+    measurer: Rc<dyn TextMeasurer>,
+    app: AppHandle,
+    // The text changed since the last layout, which changes `min_size`.
+    dirty: bool,
 
     hover: bool,
 
@@ -105,6 +123,11 @@ impl Button {
             hover: false,
             id: props.id,
             tx: ctx.tx.clone(),
+            measurer: ctx.measurer.clone(),
+            app: ctx.app.clone(),
+            dirty: false,
+            on_click: props.on_click,
+            on_hover: props.on_hover,
             anchor: props.anchor,
             image: props.image,
             color: props.color,
@@ -113,6 +136,11 @@ impl Button {
         }
     }
 
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.dirty = true;
+    }
+
     pub fn handle_message(&mut self, msg: &ButtonMessage) {
         let prev_hover = self.hover;
         match msg {
@@ -120,10 +148,24 @@ impl Button {
                 ActionState::Start => self.hover = true,
                 ActionState::End => self.hover = false,
             },
-            ButtonMessage::Click => (),
+            ButtonMessage::Click => {
+                if let Some(on_click) = &self.on_click {
+                    on_click.call(&self.app, ());
+                }
+            }
         }
 
         if prev_hover != self.hover {
+            if let Some(on_hover) = &self.on_hover {
+                on_hover.call(
+                    &self.app,
+                    if self.hover {
+                        ActionState::Start
+                    } else {
+                        ActionState::End
+                    },
+                );
+            }
             if self.hover {
                 self.progress_inc = true;
             } else {
@@ -138,8 +180,17 @@ impl Button {
 impl Element for Button {
     fn handle(&mut self, msg: &Message) {
         if msg.target == self.id {
-            if let MessageData::Button(btn_msg) = &msg.data {
-                self.handle_message(btn_msg);
+            match &msg.data {
+                MessageData::Button(btn_msg) => self.handle_message(btn_msg),
+                MessageData::Focus(FocusMessage::Activate) => {
+                    self.tx
+                        .send(Message {
+                            target: self.id,
+                            data: MessageData::Button(ButtonMessage::Click),
+                        })
+                        .unwrap();
+                }
+                _ => (),
             }
         }
     }
@@ -172,7 +223,7 @@ impl Element for Button {
         self.inner_bounds.x = self.outer_bounds.x + offset;
         self.inner_bounds.size.w = self.outer_bounds.size.w + offset;
 
-        if self.inner_bounds.contains(state.mouse_position) {
+        if state.hovered == Some(self.id) {
             if state.left_click == KeyState::Pressed {
                 self.tx
                     .send(Message {
@@ -200,22 +251,25 @@ impl Element for Button {
 
     fn draw(&self, renderer: &dyn Renderer) {
         renderer.draw_rectangle(self.inner_bounds, self.image, self.color);
+        let text_size = self
+            .measurer
+            .measure_text(&self.text, None, DEFAULT_FONT_SIZE);
         renderer.draw_text(
             Bounds {
-                x: self.inner_bounds.x + self.inner_bounds.size.w / 3.
-                    - measure_text(&self.text, None, 20, 1.0).width / 2.,
-                y: self.inner_bounds.y
-                    + self.inner_bounds.size.h / 2.
-                    + measure_text(&self.text, None, 20, 1.0).height / 2.,
+                x: self.inner_bounds.x + self.inner_bounds.size.w / 3. - text_size.w / 2.,
+                y: self.inner_bounds.y + self.inner_bounds.size.h / 2. + text_size.h / 2.,
                 size: Size { w: 0., h: 0. },
             },
             &self.text,
+            None,
+            DEFAULT_FONT_SIZE,
             WHITE,
         );
     }
 
     // Sets the outer bounds.
     fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
         let bounds = self.anchor.apply_to(bounds);
         self.outer_bounds = bounds;
 
@@ -246,18 +300,101 @@ impl Element for Button {
     }
 
     fn min_size(&self) -> Size {
-        let dim = measure_text(&self.text, None, 16, 1.0);
-        Size {
-            w: dim.width,
-            h: dim.height,
-        }
+        self.measurer
+            .measure_text(&self.text, None, DEFAULT_FONT_SIZE)
     }
 
     fn space(&self) -> Space {
         self.space
     }
 
+    fn needs_layout(&self) -> bool {
+        self.dirty
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
     fn bounds(&self) -> Bounds {
         self.inner_bounds
     }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        v.push(Focusable {
+            id: self.id,
+            bounds: self.inner_bounds,
+            text_entry: false,
+        });
+    }
+}
+
+#[test]
+pub fn test_button_draw() {
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let mut button = Button::new(
+        ButtonProps {
+            text: "Play".into(),
+            anchor: Anchor {
+                x: AnchorX::Left,
+                y: AnchorY::Top,
+            },
+            image: Some(0),
+            ..Default::default()
+        },
+        &ctx,
+    );
+    assert_eq!(button.min_size(), Size { w: 40., h: 20. });
+
+    button.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 40. },
+    });
+    let renderer = RecordingRenderer::new();
+    button.draw(&renderer);
+    assert_eq!(
+        renderer.dump(),
+        "rect x=20.0 y=0.0 w=80.0 h=40.0 texture=0 color=#ffffffff
+text x=26.7 y=30.0 w=0.0 h=0.0 \"Play\" size=20 color=#ffffffff
+"
+    );
+}
+
+#[test]
+pub fn test_button_hit_test() {
+    use crate::types::Position;
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    let ctx = UIContext::new();
+    let mut root = VListContainer::new(VListContainerProps {
+        children: (0..2)
+            .map(|_| Button::new(ButtonProps::default(), &ctx))
+            .collect(),
+        ..Default::default()
+    });
+    // Stack both buttons on top of each other.
+    for button in root.children_mut() {
+        button.set_bounds(Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 100., h: 40. },
+        });
+    }
+    let top = root.children[1].id;
+
+    let pointer = Position { x: 50., y: 20. };
+    assert_eq!(root.hit_test(pointer), Some(top));
+    assert_eq!(root.hit_test(Position { x: 50., y: 60. }), None);
+
+    root.update(&AppState {
+        mouse_position: pointer,
+        left_click: KeyState::Pressed,
+        hovered: root.hit_test(pointer),
+        ..Default::default()
+    });
+    let clicked: Vec<_> = ctx.rx.drain().map(|msg| msg.target).collect();
+    assert_eq!(clicked, vec![top]);
 }
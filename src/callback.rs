@@ -0,0 +1,74 @@
+use std::{
+    any::Any,
+    cell::{RefCell, RefMut},
+    fmt,
+    rc::Rc,
+};
+
+/// Shared handle to whatever state the application keeps, handed to every callback. Set it
+/// with `UIContext::app` before building the elements.
+#[derive(Clone)]
+pub struct AppHandle(Rc<RefCell<dyn Any>>);
+
+impl Default for AppHandle {
+    fn default() -> Self {
+        Self::new(())
+    }
+}
+
+impl AppHandle {
+    pub fn new<S: Any>(state: S) -> Self {
+        AppHandle(Rc::new(RefCell::new(state)))
+    }
+
+    /// Borrows the state if it is an `S`.
+    ///
+    /// Panics if the state is already borrowed, e.g. by the callback's caller.
+    pub fn get<S: Any>(&self) -> Option<RefMut<'_, S>> {
+        RefMut::filter_map(self.0.borrow_mut(), |state| state.downcast_mut()).ok()
+    }
+}
+
+impl fmt::Debug for AppHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AppHandle")
+    }
+}
+
+type CallbackFn<T> = dyn FnMut(&AppHandle, T);
+
+/// Closure run when an element handles one of its own messages, e.g. `ButtonProps::on_click`.
+/// Cloning it shares the closure.
+pub struct Callback<T = ()>(Rc<RefCell<CallbackFn<T>>>);
+
+impl<T> Callback<T> {
+    pub fn new(f: impl FnMut(&AppHandle, T) + 'static) -> Self {
+        Callback(Rc::new(RefCell::new(f)))
+    }
+
+    pub fn call(&self, app: &AppHandle, arg: T) {
+        (self.0.borrow_mut())(app, arg)
+    }
+}
+
+impl<T> Clone for Callback<T> {
+    fn clone(&self) -> Self {
+        Callback(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Callback<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+#[test]
+pub fn test_callback() {
+    let app = AppHandle::new(0);
+    let add = Callback::new(|app: &AppHandle, n: i32| *app.get::<i32>().unwrap() += n);
+    add.call(&app, 2);
+    add.clone().call(&app, 3);
+    assert_eq!(*app.get::<i32>().unwrap(), 5);
+    assert!(app.get::<String>().is_none());
+}
@@ -0,0 +1,71 @@
+use crate::types::{Element, Id};
+
+/// Something a container keeps per child, like the child itself or an entry wrapping it.
+pub trait ChildEntry {
+    /// Id of the element held by the entry.
+    fn child_id(&self) -> Id;
+}
+
+impl<E: Element> ChildEntry for E {
+    fn child_id(&self) -> Id {
+        self.id()
+    }
+}
+
+/// Containers whose children can be changed at runtime, addressed by the child's `Id`.
+///
+/// Changes keep the state of the children that stay and are laid out by the next layout pass.
+pub trait KeyedChildren {
+    type Entry: ChildEntry;
+
+    fn entries(&self) -> &[Self::Entry];
+    fn entries_mut(&mut self) -> &mut Vec<Self::Entry>;
+    /// Called after every change, marks the container for layout.
+    fn children_changed(&mut self);
+
+    /// Inserts an entry at `index`, or at the end if `index` is past it.
+    fn insert(&mut self, index: usize, entry: Self::Entry) {
+        let entries = self.entries_mut();
+        entries.insert(index.min(entries.len()), entry);
+        self.children_changed();
+    }
+
+    fn push(&mut self, entry: Self::Entry) {
+        self.insert(self.entries().len(), entry);
+    }
+
+    /// Removes the entry whose child has the given id.
+    fn remove(&mut self, id: Id) -> Option<Self::Entry> {
+        let index = self.position(id)?;
+        let entry = self.entries_mut().remove(index);
+        self.children_changed();
+        Some(entry)
+    }
+
+    /// Moves the entry whose child has the given id to `index`. Returns false if there is no
+    /// such entry.
+    fn move_to(&mut self, id: Id, index: usize) -> bool {
+        match self.position(id) {
+            Some(from) => {
+                let entries = self.entries_mut();
+                let entry = entries.remove(from);
+                entries.insert(index.min(entries.len()), entry);
+                self.children_changed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries_mut().clear();
+        self.children_changed();
+    }
+
+    /// Index of the entry whose child has the given id.
+    fn position(&self, id: Id) -> Option<usize> {
+        self.entries()
+            .iter()
+            .position(|entry| entry.child_id() == id)
+    }
+}
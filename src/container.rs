@@ -1 +1,241 @@
 // Single-element wrapper with padding and scaling.
+use crate::{
+    focus::Focusable,
+    types::{
+        next_id, Anchor, AnchorX, AnchorY, AppState, Bounds, Element, Id, Padding, Position,
+        Renderer, Scale, Size, Space,
+    },
+};
+
+pub struct ContainerProps<E> {
+    pub id: Id,
+    pub child: E,
+    pub padding: Padding,
+    /// Share of the padded area a `Space::Fill` child gets, never less than its `min_size`.
+    /// `Space::Minimize` children always get their `min_size`.
+    pub scale: Scale,
+    pub anchor: Anchor,
+    pub space: Space,
+}
+
+impl<E> ContainerProps<E> {
+    pub fn new(child: E) -> Self {
+        ContainerProps {
+            id: next_id(),
+            child,
+            padding: Padding::default(),
+            scale: Scale { x: 1., y: 1. },
+            anchor: Anchor {
+                x: AnchorX::Left,
+                y: AnchorY::Top,
+            },
+            space: Space::Fill,
+        }
+    }
+}
+
+pub struct Container<E> {
+    pub id: Id,
+    pub bounds: Bounds,
+    pub child: E,
+    pub padding: Padding,
+    pub scale: Scale,
+    pub anchor: Anchor,
+    pub space: Space,
+}
+
+impl<E: Element> Container<E> {
+    pub fn new(props: ContainerProps<E>) -> Self {
+        Container {
+            id: props.id,
+            bounds: Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            },
+            child: props.child,
+            padding: props.padding,
+            scale: props.scale,
+            anchor: props.anchor,
+            space: props.space,
+        }
+    }
+}
+
+impl<E: Element> Element for Container<E> {
+    fn update(&mut self, state: &AppState) {
+        self.child.update(state);
+    }
+
+    fn draw(&self, renderer: &dyn Renderer) {
+        self.child.draw(renderer);
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        let size = match self.space {
+            Space::Fill => bounds.size,
+            Space::Minimize => self.min_size(),
+        };
+        self.bounds = Bounds {
+            x: bounds.x,
+            y: bounds.y,
+            size,
+        };
+
+        let inner = self.padding.apply_to(self.bounds);
+        let child_min = self.child.min_size();
+        let child_size = match self.child.space() {
+            Space::Fill => Size {
+                w: (inner.size.w * self.scale.x).max(child_min.w),
+                h: (inner.size.h * self.scale.y).max(child_min.h),
+            },
+            Space::Minimize => child_min,
+        };
+
+        // Line up the anchor point of the child with the same point of the padded area.
+        let target = self.anchor.get_point(inner.size);
+        let offset = self.anchor.get_point(child_size);
+        self.child.set_bounds(Bounds {
+            x: inner.x + target.x - offset.x,
+            y: inner.y + target.y - offset.y,
+            size: child_size,
+        });
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn min_size(&self) -> Size {
+        let mut child = self.child.min_size();
+        // A scaled down Fill child needs a larger area to still get its own min_size.
+        if self.child.space() == Space::Fill {
+            if self.scale.x > 0. {
+                child.w /= self.scale.x;
+            }
+            if self.scale.y > 0. {
+                child.h /= self.scale.y;
+            }
+        }
+        Size {
+            w: child.w + self.padding.left + self.padding.right,
+            h: child.h + self.padding.top + self.padding.bottom,
+        }
+    }
+
+    fn space(&self) -> Space {
+        self.space
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        vec![&self.child]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        vec![&mut self.child]
+    }
+
+    fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
+        v.push(self.bounds());
+        self.child.write_all_bounds(v);
+    }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.child.can_scroll(position, wheel)
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        self.child.write_focusable(v);
+    }
+}
+
+#[test]
+pub fn test_container_layout() {
+    struct Fixed {
+        id: Id,
+        bounds: Bounds,
+        space: Space,
+    }
+    impl Element for Fixed {
+        fn update(&mut self, _state: &AppState) {}
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, bounds: Bounds) {
+            self.bounds = bounds;
+        }
+        fn bounds(&self) -> Bounds {
+            self.bounds
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 50., h: 20. }
+        }
+        fn space(&self) -> Space {
+            self.space
+        }
+        fn id(&self) -> Id {
+            self.id
+        }
+    }
+    let fixed = |space| Fixed {
+        id: next_id(),
+        bounds: Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 0., h: 0. },
+        },
+        space,
+    };
+    let bounds = Bounds {
+        x: 10.,
+        y: 10.,
+        size: Size { w: 200., h: 100. },
+    };
+
+    // The scaled child is anchored inside the padded area.
+    let mut container = Container::new(ContainerProps {
+        padding: Padding {
+            top: 10.,
+            right: 20.,
+            bottom: 30.,
+            left: 40.,
+        },
+        scale: Scale { x: 0.5, y: 0.5 },
+        anchor: Anchor {
+            x: AnchorX::Right,
+            y: AnchorY::Bottom,
+        },
+        ..ContainerProps::new(fixed(Space::Fill))
+    });
+    container.set_bounds(bounds);
+    let child = container.child.bounds;
+    assert_eq!((child.x, child.y), (120., 50.));
+    assert_eq!(child.size, Size { w: 70., h: 30. });
+
+    // The child needs 50x20, so at half scale the padded area needs to be 100x40.
+    assert_eq!(container.min_size(), Size { w: 160., h: 80. });
+    container.space = Space::Minimize;
+    container.set_bounds(bounds);
+    assert_eq!(container.child.bounds.size, Size { w: 50., h: 20. });
+
+    // Minimize children ignore scale.
+    let mut container = Container::new(ContainerProps {
+        scale: Scale { x: 0.5, y: 0.5 },
+        anchor: Anchor {
+            x: AnchorX::Middle,
+            y: AnchorY::Middle,
+        },
+        ..ContainerProps::new(fixed(Space::Minimize))
+    });
+    assert_eq!(container.min_size(), Size { w: 50., h: 20. });
+    container.set_bounds(bounds);
+    let child = container.child.bounds;
+    assert_eq!((child.x, child.y), (85., 50.));
+    assert_eq!(child.size, Size { w: 50., h: 20. });
+}
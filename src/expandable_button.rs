@@ -1,7 +1,10 @@
 use crate::{
     button::{Button, ButtonMessage, ButtonProps},
+    callback::{AppHandle, Callback},
+    focus::{FocusMessage, Focusable},
     types::{
-        AppState, Bounds, Element, Id, Message, MessageData, Renderer, Size, Space, UIContext,
+        ActionState, AppState, Bounds, Element, Event, Id, Message, MessageData, Position,
+        Renderer, Size, Space, UIContext,
     },
 };
 
@@ -9,6 +12,8 @@ pub struct ExpandableButtonProps<L> {
     pub id: Id,
     pub main: ButtonProps,
     pub list: L,
+    pub on_expand: Option<Callback>,
+    pub on_fold: Option<Callback>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,8 +29,13 @@ pub struct ExpandableButton<L> {
     bounds: Bounds,
 
     expanded: f32,
+    // Value of `expanded` the list was last laid out with.
+    laid_out_expanded: f32,
     expand_inc: bool,
+    on_expand: Option<Callback>,
+    on_fold: Option<Callback>,
     tx: flume::Sender<Message>,
+    app: AppHandle,
 }
 
 impl<L> ExpandableButton<L> {
@@ -40,8 +50,33 @@ impl<L> ExpandableButton<L> {
                 size: Size { w: 0., h: 0. },
             },
             expanded: 0.,
+            laid_out_expanded: 0.,
             expand_inc: false,
+            on_expand: props.on_expand,
+            on_fold: props.on_fold,
             tx: ctx.tx.clone(),
+            app: ctx.app.clone(),
+        }
+    }
+}
+
+impl<L: Element> ExpandableButton<L> {
+    // The list is laid out with its height scaled by the expansion, but its children keep their
+    // own size, so they have to be cut off while the list is animating.
+    fn list_clip(&self) -> Bounds {
+        let list = self.list.bounds();
+        let content = self
+            .list
+            .all_bounds()
+            .into_iter()
+            .fold(list, |acc, bounds| acc.union(bounds));
+        Bounds {
+            x: content.x,
+            y: list.y,
+            size: Size {
+                w: content.size.w,
+                h: list.size.h,
+            },
         }
     }
 }
@@ -50,14 +85,6 @@ impl<L: Element> Element for ExpandableButton<L> {
     fn update(&mut self, state: &AppState) {
         self.expanded += 1.6 * if self.expand_inc { state.dt } else { -state.dt };
         self.expanded = self.expanded.clamp(0., 1.);
-        self.list.set_bounds(Bounds {
-            x: self.bounds.x,
-            y: self.bounds.y + self.main.bounds().size.h,
-            size: Size {
-                w: self.bounds.size.w,
-                h: self.bounds.size.h * self.expanded,
-            },
-        });
 
         self.main.update(state);
         if self.expanded != 0. {
@@ -65,9 +92,11 @@ impl<L: Element> Element for ExpandableButton<L> {
         }
     }
 
-    fn handle(&mut self, msg: &Message) {
-        if msg.target == self.main.id {
-            if let MessageData::Button(ButtonMessage::Click) = msg.data {
+    fn bubble(&mut self, event: &mut Event) {
+        // Child 0 is the main button, everything else comes from the list.
+        let from_main = event.child == 0;
+        match event.msg.data {
+            MessageData::Button(ButtonMessage::Click) if from_main => {
                 self.tx
                     .send(Message {
                         target: self.id,
@@ -79,39 +108,79 @@ impl<L: Element> Element for ExpandableButton<L> {
                     })
                     .unwrap();
             }
+            MessageData::Focus(FocusMessage::Back) if self.expand_inc => {
+                // Backing out of the list folds it and hands focus back to the main button.
+                self.tx
+                    .send(Message {
+                        target: self.id,
+                        data: MessageData::ExpandableButton(ExpandableButtonMessage::Fold),
+                    })
+                    .unwrap();
+                self.tx
+                    .send(Message {
+                        target: self.main.id,
+                        data: MessageData::Focus(FocusMessage::Focus(ActionState::Start)),
+                    })
+                    .unwrap();
+                event.stop_propagation();
+            }
+            _ => (),
         }
+    }
 
-        self.main.handle(msg);
-        if self.expanded != 0. {
-            self.list.handle(msg);
-        }
-
+    fn handle(&mut self, msg: &Message) {
         if msg.target == self.id {
             if let MessageData::ExpandableButton(msg) = &msg.data {
-                match msg {
-                    ExpandableButtonMessage::Expand => {
-                        self.expand_inc = true;
-                    }
-                    ExpandableButtonMessage::Fold => {
-                        self.expand_inc = false;
+                let expand = matches!(msg, ExpandableButtonMessage::Expand);
+                if expand != self.expand_inc {
+                    self.expand_inc = expand;
+                    let callback = if expand {
+                        &self.on_expand
+                    } else {
+                        &self.on_fold
+                    };
+                    if let Some(callback) = callback {
+                        callback.call(&self.app, ());
                     }
                 }
-            } else {
-                unreachable!()
             }
         }
     }
 
     fn draw(&self, renderer: &dyn Renderer) {
         self.main.draw(renderer);
+    }
+
+    // The list drops down over whatever comes after the button, so it lives in the overlay. It
+    // is outside the button, so only its own clip decides what of it can be seen.
+    fn draw_overlay(&self, renderer: &dyn Renderer, visible: Bounds) {
+        self.main.draw_overlay(renderer, visible);
         if self.expanded != 0. {
+            renderer.push_clip_rect(self.list_clip());
             self.list.draw(renderer);
+            renderer.pop_clip_rect();
+            self.list.draw_overlay(renderer, self.list_clip());
         }
     }
 
     fn set_bounds(&mut self, bounds: Bounds) {
         self.main.set_bounds(bounds);
         self.bounds = bounds;
+        self.list.set_bounds(Bounds {
+            x: bounds.x,
+            y: bounds.y + self.main.bounds().size.h,
+            size: Size {
+                w: bounds.size.w,
+                h: bounds.size.h * self.expanded,
+            },
+        });
+        self.laid_out_expanded = self.expanded;
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.expanded != self.laid_out_expanded
+            || self.main.needs_layout()
+            || self.list.needs_layout()
     }
 
     fn bounds(&self) -> Bounds {
@@ -135,10 +204,149 @@ impl<L: Element> Element for ExpandableButton<L> {
         Space::Minimize
     }
 
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn hit_test(&self, position: Position) -> Option<Id> {
+        self.main.hit_test(position)
+    }
+
+    fn hit_test_overlay(&self, position: Position, visible: Bounds) -> Option<Id> {
+        if self.expanded == 0. {
+            // The folded list keeps its children's bounds, but none of it is visible.
+            return self.main.hit_test_overlay(position, visible);
+        }
+        self.list
+            .hit_test_overlay(position, self.list_clip())
+            .or_else(|| {
+                self.list_clip()
+                    .contains(position)
+                    .then(|| self.list.hit_test(position))
+                    .flatten()
+            })
+            .or_else(|| self.main.hit_test_overlay(position, visible))
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        vec![&self.main, &self.list]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        vec![&mut self.main, &mut self.list]
+    }
+
     fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
         self.main.write_all_bounds(v);
         if self.expanded != 0. {
             self.list.write_all_bounds(v);
         }
     }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.expanded != 0. && self.list.can_scroll(position, wheel)
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        self.main.write_focusable(v);
+        if self.expanded != 0. {
+            self.list.write_focusable(v);
+        }
+    }
+}
+
+#[test]
+pub fn test_expandable_button_callbacks() {
+    use crate::types::next_id;
+    use std::{cell::RefCell, rc::Rc};
+
+    let ctx = UIContext::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let record = |name: &'static str| {
+        let log = log.clone();
+        Some(Callback::new(move |_, ()| log.borrow_mut().push(name)))
+    };
+    let mut button = ExpandableButton::new(
+        ExpandableButtonProps {
+            id: next_id(),
+            main: ButtonProps::default(),
+            list: Button::new(ButtonProps::default(), &ctx),
+            on_expand: record("expand"),
+            on_fold: record("fold"),
+        },
+        &ctx,
+    );
+    let send = |button: &mut ExpandableButton<Button>, msg| {
+        button.handle(&Message {
+            target: button.id,
+            data: MessageData::ExpandableButton(msg),
+        })
+    };
+
+    // Only actual changes are reported.
+    send(&mut button, ExpandableButtonMessage::Fold);
+    send(&mut button, ExpandableButtonMessage::Expand);
+    send(&mut button, ExpandableButtonMessage::Expand);
+    send(&mut button, ExpandableButtonMessage::Fold);
+    assert_eq!(*log.borrow(), ["expand", "fold"]);
+}
+
+#[test]
+pub fn test_expandable_button_overlay() {
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+    use crate::types::{draw_with_overlays, hit_test_with_overlays, next_id};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let button = |text: &str| ButtonProps {
+        text: text.into(),
+        ..Default::default()
+    };
+    let dropdown = |main: &str, item: &str| {
+        ExpandableButton::new(
+            ExpandableButtonProps {
+                id: next_id(),
+                main: button(main),
+                list: Button::new(button(item), &ctx),
+                on_expand: None,
+                on_fold: None,
+            },
+            &ctx,
+        )
+    };
+    let mut first = dropdown("Menu", "Item");
+    first.handle(&Message {
+        target: first.id,
+        data: MessageData::ExpandableButton(ExpandableButtonMessage::Expand),
+    });
+    let item = first.list.id;
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![first, dropdown("Next", "Hidden")],
+        ..Default::default()
+    });
+    root.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 80. },
+    });
+    root.update(&AppState {
+        dt: 1.,
+        ..Default::default()
+    });
+    // Expanding changed the layout.
+    assert!(root.needs_layout());
+    root.set_bounds(root.bounds);
+
+    // The list covers the next button, both when drawing and when hit testing.
+    let renderer = RecordingRenderer::new();
+    draw_with_overlays(&root, &renderer);
+    assert_eq!(renderer.texts(), ["Menu", "Next", "Item"]);
+    let below_menu = Position { x: 10., y: 45. };
+    assert_eq!(root.hit_test(below_menu), Some(root.children[1].main.id));
+    assert_eq!(hit_test_with_overlays(&root, below_menu), Some(item));
 }
@@ -0,0 +1,400 @@
+use crate::types::{
+    ActionState, AppState, Bounds, Element, Id, Message, MessageData, NavigationAction, Renderer,
+    UIContext,
+};
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone)]
+pub enum FocusMessage {
+    /// Sent to an element when it gains or loses focus. Elements can also send this to
+    /// themselves to request or give up focus, e.g. when clicked.
+    Focus(ActionState),
+    /// Enter or Space was pressed while the element was focused.
+    Activate,
+    /// `NavigationAction::Back` was triggered while the element was focused.
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Index of the candidate closest to `from` in `direction`, preferring candidates that are
+/// lined up with `from` over ones that are closer but off to the side.
+pub fn nearest_in_direction(
+    from: Bounds,
+    candidates: &[Focusable],
+    direction: Direction,
+) -> Option<usize> {
+    let from = from.center();
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, candidate)| {
+            let to = candidate.bounds.center();
+            let (dx, dy) = (to.x - from.x, to.y - from.y);
+            let (primary, secondary) = match direction {
+                Direction::Up => (-dy, dx),
+                Direction::Down => (dy, dx),
+                Direction::Left => (-dx, dy),
+                Direction::Right => (dx, dy),
+            };
+            if primary <= 0. {
+                return None;
+            }
+            Some((idx, primary + secondary.abs() * 2.))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(idx, _)| idx)
+}
+
+/// An element that can receive keyboard focus, see `Element::write_focusable`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Focusable {
+    pub id: Id,
+    pub bounds: Bounds,
+    /// The element consumes Left, Right and Space itself while focused, so they don't move
+    /// focus or activate it.
+    pub text_entry: bool,
+}
+
+pub struct FocusManager {
+    focused: Option<Id>,
+    // Last known bounds of the focused element, used to find a replacement when it disappears.
+    focused_bounds: Option<Bounds>,
+    pub ring_color: Color,
+    pub ring_thickness: f32,
+
+    tx: flume::Sender<Message>,
+}
+
+impl FocusManager {
+    pub fn new(ctx: &UIContext) -> Self {
+        FocusManager {
+            focused: None,
+            focused_bounds: None,
+            ring_color: ORANGE,
+            ring_thickness: 2.,
+            tx: ctx.tx.clone(),
+        }
+    }
+
+    pub fn focused(&self) -> Option<Id> {
+        self.focused
+    }
+
+    fn send(&self, target: Id, msg: FocusMessage) {
+        self.tx
+            .send(Message {
+                target,
+                data: MessageData::Focus(msg),
+            })
+            .unwrap();
+    }
+
+    /// Moves focus to `target`, notifying both the previously and the newly focused element.
+    pub fn focus(&mut self, target: Option<Id>) {
+        if target == self.focused {
+            return;
+        }
+        if let Some(prev) = self.focused {
+            self.send(prev, FocusMessage::Focus(ActionState::End));
+        }
+        if let Some(next) = target {
+            self.send(next, FocusMessage::Focus(ActionState::Start));
+        }
+        self.focused = target;
+    }
+
+    /// Keeps track of focus requests elements send about themselves.
+    pub fn handle(&mut self, msg: &Message) {
+        if let MessageData::Focus(FocusMessage::Focus(state)) = &msg.data {
+            match state {
+                ActionState::Start if self.focused != Some(msg.target) => {
+                    if let Some(prev) = self.focused {
+                        self.send(prev, FocusMessage::Focus(ActionState::End));
+                    }
+                    self.focused = Some(msg.target);
+                }
+                ActionState::End if self.focused == Some(msg.target) => {
+                    self.focused = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Moves focus with Tab, Shift-Tab and the arrow keys in tree order and activates it with
+    /// Enter or Space. Navigation actions move focus spatially instead.
+    pub fn update(&mut self, root: &dyn Element, state: &AppState) {
+        let mut focusables = Vec::new();
+        root.write_focusable(&mut focusables);
+
+        let mut current = self
+            .focused
+            .and_then(|id| focusables.iter().position(|f| f.id == id));
+        if self.focused.is_some() && current.is_none() {
+            // The focused element was removed or hidden, move to whatever is closest to it.
+            current = self.focused_bounds.and_then(|from| {
+                focusables
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, f)| {
+                        let (a, b) = (from.center(), f.bounds.center());
+                        (idx, (a.x - b.x).powi(2) + (a.y - b.y).powi(2))
+                    })
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(idx, _)| idx)
+            });
+            self.focus(current.map(|idx| focusables[idx].id));
+        }
+
+        if let Some(action) = state.navigation {
+            self.navigate(&focusables, current, action);
+            self.focused_bounds = self
+                .focused
+                .and_then(|id| focusables.iter().find(|f| f.id == id))
+                .map(|f| f.bounds);
+            return;
+        }
+        let text_entry = current.is_some_and(|idx| focusables[idx].text_entry);
+
+        let shift = state.modifiers.shift;
+        let step: Option<isize> = if state.is_pressed(KeyCode::Tab) {
+            Some(if shift { -1 } else { 1 })
+        } else if state.is_pressed(KeyCode::Up) || !text_entry && state.is_pressed(KeyCode::Left) {
+            Some(-1)
+        } else if state.is_pressed(KeyCode::Down) || !text_entry && state.is_pressed(KeyCode::Right)
+        {
+            Some(1)
+        } else {
+            None
+        };
+
+        if let Some(step) = step {
+            if !focusables.is_empty() {
+                let len = focusables.len() as isize;
+                let next = match current {
+                    Some(idx) => (idx as isize + step).rem_euclid(len),
+                    None if step > 0 => 0,
+                    None => len - 1,
+                };
+                self.focus(Some(focusables[next as usize].id));
+            }
+        } else if let Some(focused) = self.focused {
+            if state.is_pressed(KeyCode::Enter) || !text_entry && state.is_pressed(KeyCode::Space) {
+                self.send(focused, FocusMessage::Activate);
+            }
+        }
+
+        self.focused_bounds = self
+            .focused
+            .and_then(|id| focusables.iter().find(|f| f.id == id))
+            .map(|f| f.bounds);
+    }
+
+    fn navigate(
+        &mut self,
+        focusables: &[Focusable],
+        current: Option<usize>,
+        action: NavigationAction,
+    ) {
+        let direction = match action {
+            NavigationAction::Up => Direction::Up,
+            NavigationAction::Down => Direction::Down,
+            NavigationAction::Left => Direction::Left,
+            NavigationAction::Right => Direction::Right,
+            NavigationAction::Confirm => {
+                if let Some(focused) = self.focused {
+                    self.send(focused, FocusMessage::Activate);
+                }
+                return;
+            }
+            NavigationAction::Back => {
+                if let Some(focused) = self.focused {
+                    self.send(focused, FocusMessage::Back);
+                }
+                return;
+            }
+        };
+
+        let next = match current {
+            Some(idx) => nearest_in_direction(focusables[idx].bounds, focusables, direction),
+            // Nothing is focused yet, so the first press only picks a starting point.
+            None => (!focusables.is_empty()).then_some(0),
+        };
+        if let Some(next) = next {
+            self.focus(Some(focusables[next].id));
+        }
+    }
+
+    /// Draws a ring around the focused element.
+    pub fn draw(&self, root: &dyn Element, renderer: &dyn Renderer) {
+        let focused = match self.focused {
+            Some(focused) => focused,
+            None => return,
+        };
+        let mut focusables = Vec::new();
+        root.write_focusable(&mut focusables);
+        if let Some(focusable) = focusables.iter().find(|f| f.id == focused) {
+            renderer.draw_rectangle_lines(focusable.bounds, self.ring_thickness, self.ring_color);
+        }
+    }
+}
+
+#[test]
+pub fn test_focus_traversal() {
+    use crate::button::{Button, ButtonProps};
+    use crate::types::{Key, KeyState, Modifiers};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    let ctx = UIContext::new();
+    let buttons: Vec<_> = (0..3)
+        .map(|_| Button::new(ButtonProps::default(), &ctx))
+        .collect();
+    let ids: Vec<_> = buttons.iter().map(|button| button.id).collect();
+    let root = VListContainer::new(VListContainerProps {
+        children: buttons,
+        ..Default::default()
+    });
+    let press = |keys: &[KeyCode]| AppState {
+        keys: keys
+            .iter()
+            .map(|&key| Key {
+                key,
+                state: KeyState::Pressed,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut focus = FocusManager::new(&ctx);
+    focus.update(&root, &press(&[KeyCode::Tab]));
+    assert_eq!(focus.focused(), Some(ids[0]));
+    focus.update(
+        &root,
+        &AppState {
+            modifiers: Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+            ..press(&[KeyCode::Tab])
+        },
+    );
+    assert_eq!(focus.focused(), Some(ids[2]));
+    focus.update(&root, &press(&[KeyCode::Down]));
+    assert_eq!(focus.focused(), Some(ids[0]));
+
+    // Elements asking for focus themselves take it over.
+    focus.handle(&Message {
+        target: ids[1],
+        data: MessageData::Focus(FocusMessage::Focus(ActionState::Start)),
+    });
+    assert_eq!(focus.focused(), Some(ids[1]));
+
+    ctx.rx.drain().for_each(drop);
+    focus.update(&root, &press(&[KeyCode::Enter]));
+    assert!(ctx.rx.drain().any(|msg| msg.target == ids[1]
+        && matches!(msg.data, MessageData::Focus(FocusMessage::Activate))));
+}
+
+#[test]
+pub fn test_navigation() {
+    use crate::app::App;
+    use crate::button::{Button, ButtonProps};
+    use crate::expandable_button::{ExpandableButton, ExpandableButtonProps};
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+    use crate::types::{next_id, NavigationAction, Size};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let main = ButtonProps::default();
+    let main_id = main.id;
+    let item = Button::new(ButtonProps::default(), &ctx);
+    let item_id = item.id;
+    let root = ExpandableButton::new(
+        ExpandableButtonProps {
+            id: next_id(),
+            main,
+            list: item,
+            on_expand: None,
+            on_fold: None,
+        },
+        &ctx,
+    );
+    let mut app = App::new(root, ctx, RecordingRenderer::new());
+    let main_messages = app.observer.observe(main_id);
+    let mut frame = |navigation| {
+        let state = AppState {
+            navigation,
+            dt: 1.,
+            ..Default::default()
+        };
+        app.step(Size { w: 100., h: 30. }, state, &mut |_, _| ());
+        app.focus.focused()
+    };
+
+    // Nothing is focused, so the first press picks the first focusable whatever its direction.
+    assert_eq!(frame(Some(NavigationAction::Up)), Some(main_id));
+
+    // Confirm activates the main button, which expands the list below it.
+    frame(Some(NavigationAction::Confirm));
+    assert!(main_messages
+        .drain()
+        .any(|data| matches!(data, MessageData::Focus(FocusMessage::Activate))));
+    frame(None);
+    assert_eq!(frame(Some(NavigationAction::Down)), Some(item_id));
+
+    // Back folds the list and focuses the main button again.
+    assert_eq!(frame(Some(NavigationAction::Back)), Some(main_id));
+    // Once folded there is nothing below the main button to move to.
+    frame(None);
+    assert_eq!(frame(Some(NavigationAction::Down)), Some(main_id));
+}
+
+#[test]
+pub fn test_nearest_in_direction() {
+    use crate::types::{next_id, Size};
+
+    let at = |x: f32, y: f32| Focusable {
+        id: next_id(),
+        bounds: Bounds {
+            x,
+            y,
+            size: Size { w: 10., h: 10. },
+        },
+        text_entry: false,
+    };
+    // A 2x2 grid with an extra element far off to the right of the top row.
+    let candidates = [
+        at(0., 0.),
+        at(20., 0.),
+        at(0., 20.),
+        at(20., 20.),
+        at(100., 5.),
+    ];
+
+    let from = candidates[0].bounds;
+    assert_eq!(
+        nearest_in_direction(from, &candidates, Direction::Right),
+        Some(1)
+    );
+    assert_eq!(
+        nearest_in_direction(from, &candidates, Direction::Down),
+        Some(2)
+    );
+    assert_eq!(nearest_in_direction(from, &candidates, Direction::Up), None);
+    let from = candidates[3].bounds;
+    assert_eq!(
+        nearest_in_direction(from, &candidates, Direction::Up),
+        Some(1)
+    );
+    assert_eq!(
+        nearest_in_direction(from, &candidates, Direction::Right),
+        Some(4)
+    );
+}
@@ -0,0 +1,392 @@
+use crate::{
+    children::{ChildEntry, KeyedChildren},
+    focus::Focusable,
+    types::{next_id, AppState, Bounds, Element, Id, Position, Renderer, Size, Space},
+};
+
+/// Sizing rule for a single row or column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    /// Always exactly this many pixels.
+    Fixed(f32),
+    /// Share of the space left over after fixed and fit tracks, weighted by the value.
+    Fraction(f32),
+    /// As large as the biggest `min_size` of the children placed only in this track.
+    Fit,
+}
+
+pub struct GridContainerProps<E> {
+    pub id: Id,
+    pub rows: Vec<Track>,
+    pub columns: Vec<Track>,
+    pub entries: Vec<GridEntry<E>>,
+    pub space: Space,
+    pub spacing: f32,
+}
+
+impl<E> Default for GridContainerProps<E> {
+    fn default() -> Self {
+        Self {
+            id: next_id(),
+            rows: vec![],
+            columns: vec![],
+            entries: vec![],
+            space: Space::Fill,
+            spacing: 5.,
+        }
+    }
+}
+
+pub struct GridEntry<E> {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub child: E,
+}
+
+impl<E: Element> ChildEntry for GridEntry<E> {
+    fn child_id(&self) -> Id {
+        self.child.id()
+    }
+}
+
+/// Lays out its entries on a grid of tracks.
+///
+/// Entries are placed in the cells given by their `row` and `column`. Their order in `entries`
+/// is only the draw and focus order, which is what `KeyedChildren::move_to` changes, while
+/// `place` moves an entry to another cell.
+pub struct GridContainer<E> {
+    pub id: Id,
+    pub bounds: Bounds,
+    pub rows: Vec<Track>,
+    pub columns: Vec<Track>,
+    pub entries: Vec<GridEntry<E>>,
+    pub space: Space,
+    pub spacing: f32,
+
+    // Children changed since the last layout.
+    dirty: bool,
+}
+
+impl<E> GridContainer<E> {
+    pub fn new(props: GridContainerProps<E>) -> Self {
+        GridContainer {
+            id: props.id,
+            bounds: Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            },
+            rows: props.rows,
+            columns: props.columns,
+            entries: props.entries,
+            space: props.space,
+            spacing: props.spacing,
+            dirty: false,
+        }
+    }
+}
+
+impl<E: Element> GridContainer<E> {
+    // Content size of every `Track::Fit` track, children spanning several tracks are ignored.
+    fn fit_sizes(&self) -> (Vec<f32>, Vec<f32>) {
+        let mut rows = vec![0.; self.rows.len()];
+        let mut columns = vec![0.; self.columns.len()];
+        for entry in &self.entries {
+            let min = entry.child.min_size();
+            if entry.row_span <= 1 && entry.row < rows.len() {
+                rows[entry.row] = f32::max(rows[entry.row], min.h);
+            }
+            if entry.column_span <= 1 && entry.column < columns.len() {
+                columns[entry.column] = f32::max(columns[entry.column], min.w);
+            }
+        }
+        (rows, columns)
+    }
+}
+
+/// Resolves the size of each track given the space available along that axis.
+pub fn resolve_tracks(tracks: &[Track], fit: &[f32], available: f32, spacing: f32) -> Vec<f32> {
+    let total_padding = spacing * tracks.len().saturating_sub(1) as f32;
+    let mut used = total_padding;
+    let mut fractions = 0.;
+    for (track, fit) in tracks.iter().zip(fit) {
+        match track {
+            Track::Fixed(size) => used += size,
+            Track::Fit => used += fit,
+            Track::Fraction(weight) => fractions += weight,
+        }
+    }
+    let free = (available - used).max(0.);
+
+    tracks
+        .iter()
+        .zip(fit)
+        .map(|(track, &fit)| match *track {
+            Track::Fixed(size) => size,
+            Track::Fit => fit,
+            Track::Fraction(weight) => {
+                if fractions > 0. {
+                    free * weight / fractions
+                } else {
+                    0.
+                }
+            }
+        })
+        .collect()
+}
+
+// Offset of `start` and length of the span `start..start + span`, including the inner spacing.
+fn span_extent(sizes: &[f32], start: usize, span: usize, spacing: f32) -> (f32, f32) {
+    let start = start.min(sizes.len());
+    let end = (start + span.max(1)).min(sizes.len());
+    let offset = sizes[..start].iter().map(|size| size + spacing).sum();
+    let length =
+        sizes[start..end].iter().sum::<f32>() + spacing * (end - start).saturating_sub(1) as f32;
+    (offset, length)
+}
+
+fn min_track_total(tracks: &[Track], fit: &[f32], spacing: f32) -> f32 {
+    resolve_tracks(tracks, fit, 0., spacing).iter().sum::<f32>()
+        + spacing * tracks.len().saturating_sub(1) as f32
+}
+
+impl<E: Element> GridContainer<E> {
+    /// Moves the entry whose child has the given id to another cell, keeping its spans.
+    /// Returns false if there is no such entry.
+    pub fn place(&mut self, id: Id, row: usize, column: usize) -> bool {
+        match self.position(id) {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                entry.row = row;
+                entry.column = column;
+                self.dirty = true;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<E: Element> KeyedChildren for GridContainer<E> {
+    type Entry = GridEntry<E>;
+
+    fn entries(&self) -> &[GridEntry<E>] {
+        &self.entries
+    }
+
+    fn entries_mut(&mut self) -> &mut Vec<GridEntry<E>> {
+        &mut self.entries
+    }
+
+    fn children_changed(&mut self) {
+        self.dirty = true;
+    }
+}
+
+impl<E: Element> Element for GridContainer<E> {
+    fn update(&mut self, state: &AppState) {
+        for entry in &mut self.entries {
+            entry.child.update(state);
+        }
+    }
+
+    fn draw(&self, renderer: &dyn Renderer) {
+        for entry in &self.entries {
+            entry.child.draw(renderer);
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
+        let size = match self.space {
+            Space::Fill => bounds.size,
+            Space::Minimize => self.min_size(),
+        };
+
+        let (fit_rows, fit_columns) = self.fit_sizes();
+        let rows = resolve_tracks(&self.rows, &fit_rows, size.h, self.spacing);
+        let columns = resolve_tracks(&self.columns, &fit_columns, size.w, self.spacing);
+
+        for entry in &mut self.entries {
+            let (y, h) = span_extent(&rows, entry.row, entry.row_span, self.spacing);
+            let (x, w) = span_extent(&columns, entry.column, entry.column_span, self.spacing);
+            let child_size = match entry.child.space() {
+                Space::Fill => Size { w, h },
+                Space::Minimize => entry.child.min_size(),
+            };
+
+            entry.child.set_bounds(Bounds {
+                x: bounds.x + x,
+                y: bounds.y + y,
+                size: child_size,
+            });
+        }
+
+        self.bounds = Bounds {
+            x: bounds.x,
+            y: bounds.y,
+            size,
+        };
+    }
+
+    fn min_size(&self) -> Size {
+        let (fit_rows, fit_columns) = self.fit_sizes();
+        Size {
+            w: min_track_total(&self.columns, &fit_columns, self.spacing),
+            h: min_track_total(&self.rows, &fit_rows, self.spacing),
+        }
+    }
+
+    fn space(&self) -> Space {
+        self.space
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.dirty || self.entries.iter().any(|entry| entry.child.needs_layout())
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        self.entries
+            .iter()
+            .map(|entry| &entry.child as &dyn Element)
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        self.entries
+            .iter_mut()
+            .map(|entry| &mut entry.child as &mut dyn Element)
+            .collect()
+    }
+
+    fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
+        v.push(self.bounds());
+        for entry in &self.entries {
+            entry.child.write_all_bounds(v)
+        }
+    }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.child.can_scroll(position, wheel))
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        for entry in &self.entries {
+            entry.child.write_focusable(v)
+        }
+    }
+}
+
+#[test]
+pub fn test_resolve_tracks() {
+    let tracks = [
+        Track::Fixed(100.),
+        Track::Fraction(1.),
+        Track::Fit,
+        Track::Fraction(3.),
+    ];
+    let fit = [0., 0., 40., 0.];
+    let sizes = resolve_tracks(&tracks, &fit, 390., 10.);
+    assert_eq!(sizes, vec![100., 55., 40., 165.]);
+
+    // Fractions collapse instead of going negative when space runs out.
+    let sizes = resolve_tracks(&tracks, &fit, 50., 10.);
+    assert_eq!(sizes, vec![100., 0., 40., 0.]);
+
+    let sizes = [100., 55., 40., 165.];
+    assert_eq!(span_extent(&sizes, 1, 2, 10.), (110., 105.));
+    assert_eq!(span_extent(&sizes, 0, 1, 10.), (0., 100.));
+}
+
+#[test]
+pub fn test_grid_children_changes() {
+    use crate::button::{Button, ButtonProps};
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::types::{Anchor, AnchorX, AnchorY, UIContext};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let entry = |row, column| GridEntry {
+        row,
+        column,
+        row_span: 1,
+        column_span: 1,
+        child: Button::new(
+            ButtonProps {
+                anchor: Anchor {
+                    x: AnchorX::Left,
+                    y: AnchorY::Top,
+                },
+                ..Default::default()
+            },
+            &ctx,
+        ),
+    };
+    let mut grid = GridContainer::new(GridContainerProps {
+        rows: vec![Track::Fixed(20.), Track::Fixed(30.)],
+        columns: vec![Track::Fixed(40.), Track::Fixed(50.)],
+        entries: vec![entry(0, 0), entry(1, 1)],
+        spacing: 0.,
+        ..Default::default()
+    });
+    grid.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 90., h: 50. },
+    });
+    let ids: Vec<_> = grid.entries.iter().map(|entry| entry.child.id).collect();
+    let cell_of = |grid: &GridContainer<Button>, id| {
+        let bounds = grid.entries[grid.position(id).unwrap()].child.outer_bounds;
+        (bounds.x, bounds.y, bounds.size.w, bounds.size.h)
+    };
+    let layout = |grid: &mut GridContainer<Button>| {
+        assert!(grid.needs_layout());
+        grid.set_bounds(grid.bounds);
+        assert!(!grid.needs_layout());
+    };
+
+    // Entries pushed at runtime land in their own cell.
+    grid.push(entry(0, 1));
+    let pushed = grid.entries[2].child.id;
+    layout(&mut grid);
+    assert_eq!(cell_of(&grid, pushed), (40., 0., 50., 20.));
+
+    // Moving only changes the draw order, every entry keeps its cell.
+    assert!(grid.move_to(pushed, 0));
+    layout(&mut grid);
+    assert_eq!(grid.children()[0].id(), pushed);
+    assert_eq!(cell_of(&grid, pushed), (40., 0., 50., 20.));
+    assert_eq!(cell_of(&grid, ids[1]), (40., 20., 50., 30.));
+
+    assert!(grid.place(pushed, 1, 0));
+    layout(&mut grid);
+    assert_eq!(cell_of(&grid, pushed), (0., 20., 40., 30.));
+    assert_eq!(grid.children()[0].id(), pushed);
+
+    assert_eq!(
+        grid.remove(ids[0]).map(|entry| entry.child.id),
+        Some(ids[0])
+    );
+    assert!(!grid.place(ids[0], 0, 0));
+    layout(&mut grid);
+    assert_eq!(grid.entries.len(), 2);
+
+    grid.clear();
+    assert!(grid.needs_layout());
+}
@@ -0,0 +1,289 @@
+use crate::{
+    children::KeyedChildren,
+    focus::Focusable,
+    types::{next_id, AppState, Bounds, Element, Id, Position, Renderer, Size, Space},
+};
+
+pub struct HListContainerProps<E> {
+    pub id: Id,
+    pub children: Vec<E>,
+    pub space: Space,
+    pub spacing: f32,
+}
+
+impl<E> Default for HListContainerProps<E> {
+    fn default() -> Self {
+        Self {
+            id: next_id(),
+            children: vec![],
+            space: Space::Fill,
+            spacing: 5.,
+        }
+    }
+}
+
+pub struct HListContainer<E> {
+    pub id: Id,
+    pub bounds: Bounds,
+    pub children: Vec<E>,
+    pub space: Space,
+    pub spacing: f32,
+
+    // Children changed since the last layout.
+    dirty: bool,
+}
+
+impl<E> HListContainer<E> {
+    pub fn new(props: HListContainerProps<E>) -> Self {
+        HListContainer {
+            id: props.id,
+            bounds: Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            },
+            children: props.children,
+            space: props.space,
+            spacing: props.spacing,
+            dirty: false,
+        }
+    }
+}
+
+impl<E: Element> KeyedChildren for HListContainer<E> {
+    type Entry = E;
+
+    fn entries(&self) -> &[E] {
+        &self.children
+    }
+
+    fn entries_mut(&mut self) -> &mut Vec<E> {
+        &mut self.children
+    }
+
+    fn children_changed(&mut self) {
+        self.dirty = true;
+    }
+}
+
+impl<E: Element> Element for HListContainer<E> {
+    fn update(&mut self, state: &AppState) {
+        for child in &mut self.children {
+            child.update(state);
+        }
+    }
+
+    fn draw(&self, renderer: &dyn Renderer) {
+        for child in &self.children {
+            child.draw(renderer)
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
+        let mut min_size = Size { w: 0., h: 0. };
+        let mut child_min_size = 0.;
+        let mut fill_count = 0;
+
+        for child in &self.children {
+            let min = child.min_size();
+            min_size.w += min.w;
+            min_size.h = min_size.h.max(min.h);
+
+            match child.space() {
+                Space::Fill => {
+                    fill_count += 1;
+                }
+                Space::Minimize => {
+                    child_min_size += min.w;
+                }
+            }
+        }
+        let total_padding = self.spacing * self.children.len().saturating_sub(1) as f32;
+        min_size.w += total_padding;
+
+        let size = match self.space {
+            Space::Fill => Size {
+                w: bounds.size.w,
+                h: bounds.size.h,
+            },
+            Space::Minimize => min_size,
+        };
+        let size_without_padding = Size {
+            w: size.w - total_padding,
+            h: size.h,
+        };
+
+        let free_width = size_without_padding.w - child_min_size;
+
+        let mut x = 0.;
+        for child in &mut self.children {
+            let min = child.min_size();
+            let child_size = match child.space() {
+                Space::Fill => Size {
+                    w: free_width / fill_count as f32,
+                    h: size_without_padding.h,
+                },
+                Space::Minimize => min,
+            };
+
+            child.set_bounds(Bounds {
+                x: bounds.x + x,
+                y: bounds.y,
+                size: child_size,
+            });
+            x += child.bounds().size.w + self.spacing;
+        }
+
+        self.bounds = Bounds {
+            x: bounds.x,
+            y: bounds.y,
+            size,
+        };
+    }
+
+    fn min_size(&self) -> Size {
+        let total_padding = self.spacing * self.children.len().saturating_sub(1) as f32;
+        let min_size = self.children.iter().map(|child| child.min_size()).fold(
+            Size { w: 0., h: 0. },
+            |acc, child| Size {
+                w: acc.w + child.w,
+                h: acc.h.max(child.h),
+            },
+        );
+        Size {
+            w: min_size.w + total_padding,
+            h: min_size.h,
+        }
+    }
+
+    fn space(&self) -> Space {
+        self.space
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.dirty || self.children.iter().any(|child| child.needs_layout())
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        self.children
+            .iter()
+            .map(|child| child as &dyn Element)
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        self.children
+            .iter_mut()
+            .map(|child| child as &mut dyn Element)
+            .collect()
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
+        v.push(self.bounds());
+        for child in &self.children {
+            child.write_all_bounds(v)
+        }
+    }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.children
+            .iter()
+            .any(|child| child.can_scroll(position, wheel))
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        for child in &self.children {
+            child.write_focusable(v)
+        }
+    }
+}
+
+#[test]
+pub fn test_hlist_layout() {
+    struct Fixed {
+        id: Id,
+        bounds: Bounds,
+        min: Size,
+        space: Space,
+    }
+    impl Element for Fixed {
+        fn update(&mut self, _state: &AppState) {}
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, bounds: Bounds) {
+            self.bounds = bounds;
+        }
+        fn bounds(&self) -> Bounds {
+            self.bounds
+        }
+        fn min_size(&self) -> Size {
+            self.min
+        }
+        fn space(&self) -> Space {
+            self.space
+        }
+        fn id(&self) -> Id {
+            self.id
+        }
+    }
+    let fixed = |w, space| Fixed {
+        id: next_id(),
+        bounds: Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 0., h: 0. },
+        },
+        min: Size { w, h: 10. },
+        space,
+    };
+    let mut list = HListContainer::new(HListContainerProps {
+        children: vec![
+            fixed(20., Space::Minimize),
+            fixed(10., Space::Fill),
+            fixed(10., Space::Fill),
+        ],
+        spacing: 10.,
+        ..Default::default()
+    });
+    let bounds = Bounds {
+        x: 5.,
+        y: 0.,
+        size: Size { w: 125., h: 30. },
+    };
+    let layout = |list: &HListContainer<Fixed>| {
+        list.children
+            .iter()
+            .map(|child| (child.bounds.x, child.bounds.size.w, child.bounds.size.h))
+            .collect::<Vec<_>>()
+    };
+
+    // Spacing counts towards the minimum width, just like in set_bounds.
+    assert_eq!(list.min_size(), Size { w: 60., h: 10. });
+
+    // Fill children split what the Minimize ones and the spacing leave.
+    list.set_bounds(bounds);
+    assert_eq!(
+        layout(&list),
+        vec![(5., 20., 10.), (35., 42.5, 30.), (87.5, 42.5, 30.)]
+    );
+
+    list.space = Space::Minimize;
+    list.set_bounds(bounds);
+    assert_eq!(list.bounds.size, list.min_size());
+    assert_eq!(
+        layout(&list),
+        vec![(5., 20., 10.), (35., 10., 10.), (55., 10., 10.)]
+    );
+}
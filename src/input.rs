@@ -0,0 +1,320 @@
+use crate::types::{AppState, Key, KeyState, Modifiers, Position};
+use macroquad::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Every key macroquad can report.
+pub const ALL_KEYS: [KeyCode; 120] = [
+    KeyCode::Space,
+    KeyCode::Apostrophe,
+    KeyCode::Comma,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Semicolon,
+    KeyCode::Equal,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::LeftBracket,
+    KeyCode::Backslash,
+    KeyCode::RightBracket,
+    KeyCode::GraveAccent,
+    KeyCode::World1,
+    KeyCode::World2,
+    KeyCode::Escape,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::CapsLock,
+    KeyCode::ScrollLock,
+    KeyCode::NumLock,
+    KeyCode::PrintScreen,
+    KeyCode::Pause,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::F13,
+    KeyCode::F14,
+    KeyCode::F15,
+    KeyCode::F16,
+    KeyCode::F17,
+    KeyCode::F18,
+    KeyCode::F19,
+    KeyCode::F20,
+    KeyCode::F21,
+    KeyCode::F22,
+    KeyCode::F23,
+    KeyCode::F24,
+    KeyCode::F25,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+    KeyCode::KpDecimal,
+    KeyCode::KpDivide,
+    KeyCode::KpMultiply,
+    KeyCode::KpSubtract,
+    KeyCode::KpAdd,
+    KeyCode::KpEnter,
+    KeyCode::KpEqual,
+    KeyCode::LeftShift,
+    KeyCode::LeftControl,
+    KeyCode::LeftAlt,
+    KeyCode::LeftSuper,
+    KeyCode::RightShift,
+    KeyCode::RightControl,
+    KeyCode::RightAlt,
+    KeyCode::RightSuper,
+    KeyCode::Menu,
+];
+
+const MOUSE_BUTTONS: [MouseButton; 3] =
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+/// Turns raw input into an `AppState` once per frame.
+///
+/// Events are fed in either by `poll_macroquad` or by hand, e.g. in tests. Keys and buttons
+/// that go down and up again before the next `frame` are still reported as pressed.
+#[derive(Debug, Default)]
+pub struct InputTracker {
+    mouse_position: Position,
+    wheel: Position,
+    chars: Vec<char>,
+
+    keys_down: HashSet<KeyCode>,
+    keys_tapped: HashSet<KeyCode>,
+    // Keys that aren't `KeyState::Unpressed`.
+    keys: HashMap<KeyCode, KeyState>,
+
+    buttons_down: HashSet<MouseButton>,
+    buttons_tapped: HashSet<MouseButton>,
+    buttons: HashMap<MouseButton, KeyState>,
+}
+
+impl InputTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_mouse_position(&mut self, position: Position) {
+        self.mouse_position = position;
+    }
+
+    pub fn scroll(&mut self, delta: Position) {
+        self.wheel.x += delta.x;
+        self.wheel.y += delta.y;
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        self.chars.push(c);
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) {
+        self.keys_down.insert(key);
+        self.keys_tapped.insert(key);
+    }
+
+    pub fn key_up(&mut self, key: KeyCode) {
+        self.keys_down.remove(&key);
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) {
+        self.buttons_down.insert(button);
+        self.buttons_tapped.insert(button);
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) {
+        self.buttons_down.remove(&button);
+    }
+
+    /// Reads the current input state from macroquad.
+    pub fn poll_macroquad(&mut self) {
+        let (x, y) = mouse_position();
+        self.set_mouse_position(Position { x, y });
+        let (x, y) = mouse_wheel();
+        self.scroll(Position { x, y });
+        while let Some(c) = get_char_pressed() {
+            self.type_char(c);
+        }
+        for key in ALL_KEYS {
+            if is_key_down(key) {
+                self.key_down(key);
+            } else {
+                self.key_up(key);
+            }
+        }
+        for button in MOUSE_BUTTONS {
+            if is_mouse_button_down(button) {
+                self.mouse_down(button);
+            } else {
+                self.mouse_up(button);
+            }
+        }
+    }
+
+    /// Advances every key and button by one frame and returns the resulting state. Wheel
+    /// movement and typed characters are only reported once.
+    pub fn frame(&mut self, dt: f32) -> AppState {
+        advance(&mut self.keys, &self.keys_down, &mut self.keys_tapped);
+        advance(
+            &mut self.buttons,
+            &self.buttons_down,
+            &mut self.buttons_tapped,
+        );
+
+        let button = |button| {
+            self.buttons
+                .get(&button)
+                .copied()
+                .unwrap_or(KeyState::Unpressed)
+        };
+        let is_down = |keys: &[KeyCode]| {
+            keys.iter()
+                .any(|key| matches!(self.keys.get(key), Some(KeyState::Pressed | KeyState::Held)))
+        };
+        let mut keys: Vec<_> = self
+            .keys
+            .iter()
+            .map(|(&key, &state)| Key { key, state })
+            .collect();
+        // Hash map order changes between runs, keep the list stable.
+        keys.sort_by_key(|key| ALL_KEYS.iter().position(|&k| k == key.key));
+
+        AppState {
+            mouse_position: self.mouse_position,
+            wheel: std::mem::take(&mut self.wheel),
+            right_click: button(MouseButton::Right),
+            left_click: button(MouseButton::Left),
+            middle_click: button(MouseButton::Middle),
+            input: std::mem::take(&mut self.chars),
+            modifiers: Modifiers {
+                shift: is_down(&[KeyCode::LeftShift, KeyCode::RightShift]),
+                ctrl: is_down(&[KeyCode::LeftControl, KeyCode::RightControl]),
+                alt: is_down(&[KeyCode::LeftAlt, KeyCode::RightAlt]),
+                logo: is_down(&[KeyCode::LeftSuper, KeyCode::RightSuper]),
+            },
+            keys,
+            dt,
+            ..Default::default()
+        }
+    }
+}
+
+fn advance<T: Copy + Eq + std::hash::Hash>(
+    states: &mut HashMap<T, KeyState>,
+    down: &HashSet<T>,
+    tapped: &mut HashSet<T>,
+) {
+    for &item in down.iter().chain(tapped.iter()) {
+        states.entry(item).or_insert(KeyState::Unpressed);
+    }
+    states.retain(|item, state| {
+        let is_down = down.contains(item) || tapped.contains(item);
+        *state = state.advance(is_down);
+        *state != KeyState::Unpressed
+    });
+    tapped.clear();
+}
+
+#[test]
+pub fn test_input_tracker() {
+    let mut input = InputTracker::new();
+    let state_of = |state: &AppState, key| {
+        state
+            .keys
+            .iter()
+            .find(|k| k.key == key)
+            .map(|k| k.state)
+            .unwrap_or(KeyState::Unpressed)
+    };
+
+    input.key_down(KeyCode::LeftShift);
+    input.key_down(KeyCode::A);
+    input.type_char('A');
+    input.mouse_down(MouseButton::Left);
+    input.scroll(Position { x: 0., y: 1. });
+    let state = input.frame(0.1);
+    assert_eq!(state_of(&state, KeyCode::A), KeyState::Pressed);
+    assert_eq!(state.left_click, KeyState::Pressed);
+    assert_eq!(state.input, vec!['A']);
+    assert_eq!(state.wheel.y, 1.);
+    assert!(state.modifiers.shift && !state.modifiers.ctrl);
+
+    input.key_up(KeyCode::A);
+    let state = input.frame(0.1);
+    assert_eq!(state_of(&state, KeyCode::A), KeyState::Released);
+    assert_eq!(state_of(&state, KeyCode::LeftShift), KeyState::Held);
+    assert_eq!(state.left_click, KeyState::Held);
+    assert!(state.input.is_empty());
+    assert_eq!(state.wheel.y, 0.);
+
+    // A tap shorter than a frame still counts as a press.
+    input.mouse_up(MouseButton::Left);
+    input.key_down(KeyCode::Enter);
+    input.key_up(KeyCode::Enter);
+    let state = input.frame(0.1);
+    assert_eq!(state_of(&state, KeyCode::A), KeyState::Unpressed);
+    assert_eq!(state_of(&state, KeyCode::Enter), KeyState::Pressed);
+    assert_eq!(state.left_click, KeyState::Released);
+    let state = input.frame(0.1);
+    assert_eq!(state_of(&state, KeyCode::Enter), KeyState::Released);
+    assert_eq!(state.left_click, KeyState::Unpressed);
+}
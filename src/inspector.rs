@@ -0,0 +1,232 @@
+use crate::types::{
+    hit_test_with_overlays, AppState, Bounds, Element, Id, Position, Renderer, Size, TextMeasurer,
+    UIContext,
+};
+use macroquad::prelude::*;
+use std::rc::Rc;
+
+const FONT_SIZE: u16 = 16;
+const PADDING: f32 = 4.;
+
+/// Debug overlay that outlines every element and describes the one under the pointer.
+///
+/// `toggle_key` shows and hides it. While it is shown, `freeze_key` stops updating the UI and
+/// `step_key` runs a single frame of the frozen UI.
+pub struct Inspector {
+    pub enabled: bool,
+    pub frozen: bool,
+    pub toggle_key: KeyCode,
+    pub freeze_key: KeyCode,
+    pub step_key: KeyCode,
+    pub bounds_color: Color,
+    pub hovered_color: Color,
+    pub panel_color: Color,
+    pub text_color: Color,
+
+    measurer: Rc<dyn TextMeasurer>,
+}
+
+impl Inspector {
+    pub fn new(ctx: &UIContext) -> Self {
+        Inspector {
+            enabled: false,
+            frozen: false,
+            toggle_key: KeyCode::F12,
+            freeze_key: KeyCode::F11,
+            step_key: KeyCode::F10,
+            bounds_color: Color { a: 0.6, ..SKYBLUE },
+            hovered_color: MAGENTA,
+            panel_color: Color { a: 0.8, ..BLACK },
+            text_color: WHITE,
+            measurer: ctx.measurer.clone(),
+        }
+    }
+
+    /// Handles the hotkeys. Returns whether the UI should be updated this frame.
+    pub fn update(&mut self, state: &AppState) -> bool {
+        if state.is_pressed(self.toggle_key) {
+            self.enabled = !self.enabled;
+            self.frozen = false;
+        }
+        if !self.enabled {
+            return true;
+        }
+        if state.is_pressed(self.freeze_key) {
+            self.frozen = !self.frozen;
+        }
+        !self.frozen || state.is_pressed(self.step_key)
+    }
+
+    /// The element the pointer at `position` hovers, overlays included.
+    pub fn inspected(&self, root: &dyn Element, position: Position) -> Option<Id> {
+        hit_test_with_overlays(root, position)
+    }
+
+    pub fn draw(&self, root: &dyn Element, renderer: &dyn Renderer, pointer: Position) {
+        if !self.enabled {
+            return;
+        }
+        root.walk(&mut |_, element| {
+            renderer.draw_rectangle_lines(element.bounds(), 1., self.bounds_color);
+        });
+
+        let element = match self.inspected(root, pointer).and_then(|id| root.find(id)) {
+            Some(element) => element,
+            None => return,
+        };
+        let bounds = element.bounds();
+        let min_size = element.min_size();
+        renderer.draw_rectangle_lines(bounds, 2., self.hovered_color);
+
+        let mut lines = vec![
+            element.type_name().to_owned(),
+            format!("{:?}", element.id()),
+            format!(
+                "bounds x={:.1} y={:.1} w={:.1} h={:.1}",
+                bounds.x, bounds.y, bounds.size.w, bounds.size.h
+            ),
+            format!("min_size w={:.1} h={:.1}", min_size.w, min_size.h),
+            format!("space {:?}", element.space()),
+        ];
+        if self.frozen {
+            lines.push("frozen".to_owned());
+        }
+
+        let line_height = FONT_SIZE as f32;
+        let width = lines
+            .iter()
+            .map(|line| self.measurer.measure_text(line, None, FONT_SIZE).w)
+            .fold(0., f32::max);
+        let panel = Bounds {
+            x: pointer.x + 12.,
+            y: pointer.y + 12.,
+            size: Size {
+                w: width + PADDING * 2.,
+                h: line_height * lines.len() as f32 + PADDING * 2.,
+            },
+        };
+        renderer.draw_rectangle(panel, None, self.panel_color);
+        for (idx, line) in lines.iter().enumerate() {
+            renderer.draw_text(
+                Bounds {
+                    x: panel.x + PADDING,
+                    y: panel.y + PADDING + line_height * (idx + 1) as f32,
+                    size: Size { w: 0., h: 0. },
+                },
+                line,
+                None,
+                FONT_SIZE,
+                self.text_color,
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_inspector() {
+    use crate::button::{Button, ButtonProps};
+    use crate::expandable_button::{
+        ExpandableButton, ExpandableButtonMessage, ExpandableButtonProps,
+    };
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+    use crate::types::{next_id, Key, KeyState, Message, MessageData};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![Button::new(ButtonProps::default(), &ctx)],
+        ..Default::default()
+    });
+    root.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 100. },
+    });
+    let press = |key| AppState {
+        keys: vec![Key {
+            key,
+            state: KeyState::Pressed,
+        }],
+        ..Default::default()
+    };
+
+    let mut inspector = Inspector::new(&ctx);
+    let renderer = RecordingRenderer::new();
+    let button = root.children[0].bounds();
+    let pointer = Position {
+        x: button.x + 1.,
+        y: button.y + 1.,
+    };
+    inspector.draw(&root, &renderer, pointer);
+    assert!(renderer.take().is_empty());
+
+    assert!(inspector.update(&press(KeyCode::F12)));
+    assert!(!inspector.update(&press(KeyCode::F11)));
+    assert!(!inspector.update(&AppState::default()));
+    assert!(inspector.update(&press(KeyCode::F10)));
+
+    assert_eq!(
+        inspector.inspected(&root, pointer),
+        Some(root.children[0].id)
+    );
+    inspector.draw(&root, &renderer, pointer);
+    let texts = renderer.texts();
+    assert_eq!(texts[0], "nui::button::Button");
+    assert_eq!(texts[4], "space Fill");
+    assert_eq!(texts[5], "frozen");
+    renderer.clear();
+
+    // An open dropdown covers what is below it.
+    let dropdown = |main: &str, item: &str| {
+        let item = Button::new(
+            ButtonProps {
+                text: item.into(),
+                ..Default::default()
+            },
+            &ctx,
+        );
+        ExpandableButton::new(
+            ExpandableButtonProps {
+                id: next_id(),
+                main: ButtonProps {
+                    text: main.into(),
+                    ..Default::default()
+                },
+                list: item,
+                on_expand: None,
+                on_fold: None,
+            },
+            &ctx,
+        )
+    };
+    let mut first = dropdown("Menu", "Item");
+    first.handle(&Message {
+        target: first.id(),
+        data: MessageData::ExpandableButton(ExpandableButtonMessage::Expand),
+    });
+    let item = first.children()[1].id();
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![first, dropdown("Next", "Hidden")],
+        ..Default::default()
+    });
+    let bounds = Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 80. },
+    };
+    root.set_bounds(bounds);
+    root.update(&AppState {
+        dt: 1.,
+        ..Default::default()
+    });
+    root.set_bounds(bounds);
+
+    let below_menu = Position { x: 10., y: 45. };
+    assert_eq!(
+        root.hit_test(below_menu),
+        Some(root.children[1].children()[0].id())
+    );
+    assert_eq!(inspector.inspected(&root, below_menu), Some(item));
+    inspector.draw(&root, &renderer, below_menu);
+    assert_eq!(renderer.texts()[1], format!("{:?}", item));
+}
@@ -1,6 +1,19 @@
 pub mod anchor_container;
+pub mod app;
 pub mod button;
+pub mod callback;
+pub mod children;
 pub mod container;
 pub mod expandable_button;
+pub mod focus;
+pub mod grid_container;
+pub mod hlist_container;
+pub mod input;
+pub mod inspector;
+pub mod recording_renderer;
+pub mod router;
+pub mod scroll_container;
+pub mod test_driver;
+pub mod text_input;
 pub mod types;
-pub mod vlist_container;
\ No newline at end of file
+pub mod vlist_container;
@@ -1,10 +1,13 @@
 use macroquad::prelude::*;
 use nui::anchor_container::{AnchorContainer, AnchorContainerProps, AnchorEntry};
-use nui::button::{Button, ButtonMessage, ButtonProps};
+use nui::app::App;
+use nui::button::{Button, ButtonProps};
+use nui::callback::Callback;
 use nui::expandable_button::{ExpandableButton, ExpandableButtonProps};
+use nui::text_input::{TextInput, TextInputProps};
 use nui::types::{
-    next_id, Anchor, AnchorX, AnchorY, AppState, Bounds, Element, EventObserver, KeyState,
-    MacroquadRenderer, MessageData, Position, PreserveRatio, Scale, Size, Space, UIContext,
+    next_id, Anchor, AnchorX, AnchorY, Element, MacroquadRenderer, PreserveRatio, Scale, Space,
+    UIContext,
 };
 use nui::vlist_container::{VListContainer, VListContainerProps};
 
@@ -17,9 +20,6 @@ async fn main() {
     ));
 
     let ctx = UIContext::new();
-    let mut observer = EventObserver::new();
-    let first_hello_world = next_id();
-    let first_hello_world_observer = observer.observe(first_hello_world);
     let button_props = std::iter::once(ButtonProps {
         text: "Clickable".into(),
         space: Space::Fill,
@@ -27,9 +27,9 @@ async fn main() {
             x: AnchorX::Right,
             y: AnchorY::Top,
         },
-        id: first_hello_world,
         image: Some(button_texture),
         preserve_ratio: PreserveRatio::Height(7. / 1.),
+        on_click: Some(Callback::new(|_, ()| println!("Click!"))),
         ..Default::default()
     })
     .chain(
@@ -87,7 +87,7 @@ async fn main() {
         ..Default::default()
     }));*/
 
-    let mut elem: Box<dyn Element> = Box::new(AnchorContainer::new(AnchorContainerProps {
+    let elem = AnchorContainer::new(AnchorContainerProps {
         entries: vec![AnchorEntry {
             scale: Scale { x: 0.5, y: 1.0 },
             anchor: Anchor {
@@ -109,103 +109,29 @@ async fn main() {
                         ..Default::default()
                     },
                     list: VListContainer::new(VListContainerProps {
-                        children: button_props.map(|prop| Button::new(prop, &ctx)).collect(),
+                        children: std::iter::once(
+                            TextInput::new(
+                                TextInputProps {
+                                    placeholder: "Search".into(),
+                                    ..Default::default()
+                                },
+                                &ctx,
+                            )
+                            .boxed(),
+                        )
+                        .chain(button_props.map(|prop| Button::new(prop, &ctx).boxed()))
+                        .collect(),
                         ..Default::default()
                     }),
+                    on_expand: None,
+                    on_fold: None,
                 },
                 &ctx,
             ),
         }],
         ..Default::default()
-    }));
-
-    let mut screen_size = (0., 0.);
-    let mut left_click = KeyState::Released;
-    let mut right_click = KeyState::Released;
-
-    loop {
-        rand::srand(0);
-        clear_background(WHITE);
-
-        let frame_screen_size = (screen_width(), screen_height());
-        if frame_screen_size != screen_size {
-            elem.set_bounds(Bounds {
-                x: 0.,
-                y: 0.,
-                size: Size {
-                    w: screen_width(),
-                    h: screen_height(),
-                },
-            });
-            screen_size = frame_screen_size;
-        }
-
-        if is_mouse_button_down(MouseButton::Left) {
-            left_click = match left_click {
-                KeyState::Released | KeyState::Unpressed => KeyState::Pressed,
-                KeyState::Held | KeyState::Pressed => KeyState::Held,
-            }
-        } else {
-            left_click = match left_click {
-                KeyState::Released | KeyState::Unpressed => KeyState::Unpressed,
-                KeyState::Held | KeyState::Pressed => KeyState::Released,
-            }
-        }
-
-        if is_mouse_button_down(MouseButton::Right) {
-            right_click = match right_click {
-                KeyState::Released | KeyState::Unpressed => KeyState::Pressed,
-                KeyState::Held | KeyState::Pressed => KeyState::Held,
-            }
-        } else {
-            right_click = match right_click {
-                KeyState::Released | KeyState::Unpressed => KeyState::Unpressed,
-                KeyState::Held | KeyState::Pressed => KeyState::Released,
-            }
-        }
-
-        elem.update(&AppState {
-            mouse_position: Position {
-                x: mouse_position().0,
-                y: mouse_position().1,
-            },
-            right_click,
-            left_click,
-            input: None,
-            keys: vec![],
-            dt: get_frame_time(),
-        });
-
-        for msg in ctx.rx.drain() {
-            elem.handle(&msg);
-            observer.handle(msg);
-        }
-
-        for msg in first_hello_world_observer.drain() {
-            if let MessageData::Button(ButtonMessage::Click) = msg {
-                println!("Click!");
-            }
-        }
-
-        elem.draw(&renderer);
-
-        /*let mut bounds = Vec::new();
-        elem.write_all_bounds(&mut bounds);
-        for bound in bounds {
-            draw_rectangle(
-                bound.x,
-                bound.y,
-                bound.size.w,
-                bound.size.h,
-                Color {
-                    r: rand::gen_range(0., 1.),
-                    g: rand::gen_range(0., 1.),
-                    b: rand::gen_range(0., 1.),
-                    a: 0.5,
-                },
-            );
-        }*/
+    })
+    .boxed();
 
-        next_frame().await
-    }
+    App::new(elem, ctx, renderer).run(|_, _| ()).await;
 }
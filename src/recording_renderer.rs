@@ -0,0 +1,247 @@
+use crate::types::{Bounds, ClipStack, Renderer, Size, TextMeasurer};
+use macroquad::prelude::*;
+use std::{cell::RefCell, fmt};
+
+/// A single call made to a `Renderer`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    Rectangle {
+        bounds: Bounds,
+        texture: Option<u32>,
+        color: Color,
+    },
+    RectangleLines {
+        bounds: Bounds,
+        thickness: f32,
+        color: Color,
+    },
+    Text {
+        bounds: Bounds,
+        text: String,
+        font: Option<Font>,
+        font_size: u16,
+        color: Color,
+    },
+    /// Contains the effective clip rect, already intersected with the enclosing ones.
+    PushClip(Bounds),
+    PopClip,
+}
+
+struct DisplayBounds(Bounds);
+
+impl fmt::Display for DisplayBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = self.0;
+        write!(
+            f,
+            "x={:.1} y={:.1} w={:.1} h={:.1}",
+            bounds.x, bounds.y, bounds.size.w, bounds.size.h
+        )
+    }
+}
+
+struct DisplayColor(Color);
+
+impl fmt::Display for DisplayColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a]: [u8; 4] = self.0.into();
+        write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+impl fmt::Display for DrawCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawCommand::Rectangle {
+                bounds,
+                texture,
+                color,
+            } => {
+                write!(f, "rect {}", DisplayBounds(*bounds))?;
+                if let Some(texture) = texture {
+                    write!(f, " texture={}", texture)?;
+                }
+                write!(f, " color={}", DisplayColor(*color))
+            }
+            DrawCommand::RectangleLines {
+                bounds,
+                thickness,
+                color,
+            } => write!(
+                f,
+                "lines {} thickness={:.1} color={}",
+                DisplayBounds(*bounds),
+                thickness,
+                DisplayColor(*color)
+            ),
+            DrawCommand::Text {
+                bounds,
+                text,
+                font,
+                font_size,
+                color,
+            } => {
+                write!(f, "text {} {:?}", DisplayBounds(*bounds), text)?;
+                if font.is_some() {
+                    write!(f, " custom-font")?;
+                }
+                write!(f, " size={} color={}", font_size, DisplayColor(*color))
+            }
+            DrawCommand::PushClip(bounds) => write!(f, "clip {}", DisplayBounds(*bounds)),
+            DrawCommand::PopClip => write!(f, "unclip"),
+        }
+    }
+}
+
+/// Renderer that draws nothing and records every call instead, so drawing can be tested
+/// without a window.
+#[derive(Default)]
+pub struct RecordingRenderer {
+    commands: RefCell<Vec<DrawCommand>>,
+    clip_stack: RefCell<ClipStack>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> Vec<DrawCommand> {
+        self.commands.borrow().clone()
+    }
+
+    /// The strings of the recorded text commands, in draw order.
+    pub fn texts(&self) -> Vec<String> {
+        self.commands
+            .borrow()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the recorded commands and starts a new recording.
+    pub fn take(&self) -> Vec<DrawCommand> {
+        self.commands.take()
+    }
+
+    pub fn clear(&self) {
+        self.commands.borrow_mut().clear();
+    }
+
+    /// One line per command, indented by how many clip rects are active.
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        let mut depth: usize = 0;
+        for command in self.commands.borrow().iter() {
+            if *command == DrawCommand::PopClip {
+                depth = depth.saturating_sub(1);
+            }
+            out.push_str(&"  ".repeat(depth));
+            out.push_str(&command.to_string());
+            out.push('\n');
+            if let DrawCommand::PushClip(_) = command {
+                depth += 1;
+            }
+        }
+        out
+    }
+
+    fn record(&self, command: DrawCommand) {
+        self.commands.borrow_mut().push(command);
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn draw_rectangle(&self, bounds: Bounds, texture: Option<u32>, color: Color) {
+        self.record(DrawCommand::Rectangle {
+            bounds,
+            texture,
+            color,
+        });
+    }
+
+    fn draw_text(
+        &self,
+        bounds: Bounds,
+        text: &str,
+        font: Option<Font>,
+        font_size: u16,
+        color: Color,
+    ) {
+        self.record(DrawCommand::Text {
+            bounds,
+            text: text.to_owned(),
+            font,
+            font_size,
+            color,
+        });
+    }
+
+    fn draw_rectangle_lines(&self, bounds: Bounds, thickness: f32, color: Color) {
+        self.record(DrawCommand::RectangleLines {
+            bounds,
+            thickness,
+            color,
+        });
+    }
+
+    fn push_clip_rect(&self, bounds: Bounds) {
+        let clip = self.clip_stack.borrow_mut().push(bounds);
+        self.record(DrawCommand::PushClip(clip));
+    }
+
+    fn pop_clip_rect(&self) {
+        self.clip_stack.borrow_mut().pop();
+        self.record(DrawCommand::PopClip);
+    }
+}
+
+/// Deterministic stand-in for real font metrics: every char is half as wide as the font size
+/// and every line is exactly as high as the font size.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MonospaceTextMeasurer;
+
+impl TextMeasurer for MonospaceTextMeasurer {
+    fn measure_text(&self, text: &str, _font: Option<Font>, font_size: u16) -> Size {
+        let font_size = font_size as f32;
+        Size {
+            w: text.chars().count() as f32 * font_size / 2.,
+            h: if text.is_empty() { 0. } else { font_size },
+        }
+    }
+}
+
+#[test]
+pub fn test_recording_renderer_dump() {
+    let renderer = RecordingRenderer::new();
+    let bounds = |x: f32, y: f32, w: f32, h: f32| Bounds {
+        x,
+        y,
+        size: Size { w, h },
+    };
+    renderer.draw_rectangle(bounds(0., 0., 100., 50.), Some(2), WHITE);
+    renderer.push_clip_rect(bounds(10., 10., 50., 50.));
+    renderer.push_clip_rect(bounds(0., 20., 100., 10.));
+    renderer.draw_text(bounds(12., 25., 0., 0.), "Hi \"you\"", None, 16, BLACK);
+    renderer.pop_clip_rect();
+    renderer.draw_rectangle_lines(bounds(10., 10., 20., 20.), 2., RED);
+    renderer.pop_clip_rect();
+
+    assert_eq!(
+        renderer.dump(),
+        "rect x=0.0 y=0.0 w=100.0 h=50.0 texture=2 color=#ffffffff
+clip x=10.0 y=10.0 w=50.0 h=50.0
+  clip x=10.0 y=20.0 w=50.0 h=10.0
+    text x=12.0 y=25.0 w=0.0 h=0.0 \"Hi \\\"you\\\"\" size=16 color=#000000ff
+  unclip
+  lines x=10.0 y=10.0 w=20.0 h=20.0 thickness=2.0 color=#e52838ff
+unclip
+"
+    );
+    assert_eq!(renderer.texts(), ["Hi \"you\""]);
+    assert_eq!(renderer.take().len(), 7);
+    assert!(renderer.commands().is_empty());
+}
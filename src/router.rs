@@ -0,0 +1,237 @@
+use crate::types::{Element, Event, Id, Message, Phase};
+use std::collections::HashMap;
+
+/// Delivers messages straight to their target using an index of where every `Id` lives in
+/// the element tree, instead of offering every message to every element.
+///
+/// A path is the list of child indices, as returned by `Element::children`, leading from the
+/// root to an element.
+#[derive(Debug, Default)]
+pub struct Router {
+    paths: HashMap<Id, Vec<usize>>,
+}
+
+// Path to the element with `id`, searching the tree depth first.
+fn find(element: &dyn Element, id: Id, path: &mut Vec<usize>) -> bool {
+    if element.id() == id {
+        return true;
+    }
+    for (idx, child) in element.children().into_iter().enumerate() {
+        path.push(idx);
+        if find(child, id, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+// Captures the event on every ancestor along `path`, handles it on the target and bubbles it
+// back up, stopping wherever propagation is stopped.
+fn propagate(element: &mut dyn Element, path: &[usize], event: &mut Event) {
+    let (&child, rest) = match path.split_first() {
+        Some(split) => split,
+        None => {
+            element.handle(event.msg);
+            return;
+        }
+    };
+
+    event.phase = Phase::Capture;
+    event.child = child;
+    element.capture(event);
+    if event.is_stopped() {
+        return;
+    }
+
+    if let Some(child) = element.children_mut().into_iter().nth(child) {
+        propagate(child, rest, event);
+    }
+    if event.is_stopped() {
+        return;
+    }
+
+    event.phase = Phase::Bubble;
+    event.child = child;
+    element.bubble(event);
+}
+
+fn deliver_all(element: &mut dyn Element, msg: &Message) {
+    element.handle(msg);
+    for child in element.children_mut() {
+        deliver_all(child, msg);
+    }
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes `root` and everything below it, replacing the previous index.
+    pub fn mount(&mut self, root: &dyn Element) {
+        self.paths.clear();
+        root.walk(&mut |path, element| {
+            self.paths.insert(element.id(), path.to_vec());
+        });
+    }
+
+    pub fn path(&self, id: Id) -> Option<&[usize]> {
+        self.paths.get(&id).map(|path| path.as_slice())
+    }
+
+    /// Delivers `msg` to its target, passing it through the target's ancestors from the root
+    /// down (`Element::capture`) and back up (`Element::bubble`). Returns false if the target
+    /// isn't part of the tree.
+    ///
+    /// Targets that aren't indexed or moved since are looked up in the tree and indexed, so
+    /// trees can be changed freely between dispatches.
+    pub fn dispatch(&mut self, root: &mut dyn Element, msg: &Message) -> bool {
+        let target = msg.target;
+        let indexed = self
+            .paths
+            .get(&target)
+            .and_then(|path| root.descendant(path))
+            .is_some_and(|element| element.id() == target);
+        if !indexed {
+            let mut path = Vec::new();
+            if !find(root, target, &mut path) {
+                self.paths.remove(&target);
+                return false;
+            }
+            self.paths.insert(target, path);
+        }
+
+        propagate(root, &self.paths[&target], &mut Event::new(msg));
+        true
+    }
+
+    /// Offers `msg` to every element in the tree, regardless of its target. This is how
+    /// messages made with `Message::broadcast` are delivered.
+    pub fn broadcast(root: &mut dyn Element, msg: &Message) {
+        deliver_all(root, msg);
+    }
+}
+
+#[test]
+pub fn test_router() {
+    use crate::types::{next_id, AppState, Bounds, MessageData, Renderer, Size, Space};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+    use std::{cell::RefCell, rc::Rc};
+
+    type Log = Rc<RefCell<Vec<(&'static str, Id)>>>;
+
+    // Element that writes down every message it sees, and in which phase.
+    struct Probe {
+        id: Id,
+        log: Log,
+        children: Vec<Probe>,
+        stop: Option<Phase>,
+    }
+
+    impl Probe {
+        fn observe(&mut self, event: &mut Event, name: &'static str) {
+            self.log.borrow_mut().push((name, self.id));
+            if self.stop == Some(event.phase) {
+                event.stop_propagation();
+            }
+        }
+    }
+
+    impl Element for Probe {
+        fn update(&mut self, _state: &AppState) {}
+        fn handle(&mut self, _msg: &Message) {
+            self.log.borrow_mut().push(("handle", self.id));
+        }
+        fn capture(&mut self, event: &mut Event) {
+            self.observe(event, "capture");
+        }
+        fn bubble(&mut self, event: &mut Event) {
+            self.observe(event, "bubble");
+        }
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, _bounds: Bounds) {}
+        fn bounds(&self) -> Bounds {
+            Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            }
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 0., h: 0. }
+        }
+        fn space(&self) -> Space {
+            Space::Fill
+        }
+        fn id(&self) -> Id {
+            self.id
+        }
+        fn children(&self) -> Vec<&dyn Element> {
+            self.children.iter().map(|c| c as &dyn Element).collect()
+        }
+        fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+            self.children
+                .iter_mut()
+                .map(|c| c as &mut dyn Element)
+                .collect()
+        }
+    }
+
+    let log = Log::default();
+    let probe = |children| Probe {
+        id: next_id(),
+        log: log.clone(),
+        children,
+        stop: None,
+    };
+    let inner = probe(vec![probe(vec![]), probe(vec![])]);
+    let inner_id = inner.id;
+    let leaf_ids: Vec<_> = inner.children.iter().map(|probe| probe.id).collect();
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![inner],
+        ..Default::default()
+    });
+
+    let mut router = Router::new();
+    router.mount(&root);
+    assert_eq!(router.path(leaf_ids[1]), Some(&[0, 1][..]));
+
+    let msg = |target| Message {
+        target,
+        data: MessageData::Null,
+    };
+    assert!(router.dispatch(&mut root, &msg(leaf_ids[1])));
+    assert_eq!(
+        log.take(),
+        vec![
+            ("capture", inner_id),
+            ("handle", leaf_ids[1]),
+            ("bubble", inner_id)
+        ]
+    );
+
+    root.children[0].stop = Some(Phase::Capture);
+    assert!(router.dispatch(&mut root, &msg(leaf_ids[0])));
+    assert_eq!(log.take(), vec![("capture", inner_id)]);
+    root.children[0].stop = None;
+
+    // Reordering invalidates the path, which is looked up again on the next dispatch.
+    root.children[0].children.swap(0, 1);
+    assert!(router.dispatch(&mut root, &msg(leaf_ids[1])));
+    assert_eq!(router.path(leaf_ids[1]), Some(&[0, 0][..]));
+    assert_eq!(log.take()[1], ("handle", leaf_ids[1]));
+
+    // So are elements added after mounting.
+    let added = probe(vec![]);
+    let added_id = added.id;
+    root.children[0].children.push(added);
+    assert!(router.dispatch(&mut root, &msg(added_id)));
+    assert_eq!(router.path(added_id), Some(&[0, 2][..]));
+    assert_eq!(log.take()[1], ("handle", added_id));
+
+    assert!(!router.dispatch(&mut root, &msg(next_id())));
+
+    Router::broadcast(&mut root, &Message::broadcast(MessageData::Null));
+    assert_eq!(log.take().len(), 4);
+}
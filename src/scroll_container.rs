@@ -0,0 +1,716 @@
+use crate::{
+    callback::{AppHandle, Callback},
+    focus::Focusable,
+    types::{
+        next_id, AppState, Bounds, Element, Id, KeyState, Message, MessageData, Position, Renderer,
+        Size, Space, UIContext,
+    },
+};
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone)]
+pub enum ScrollContainerMessage {
+    /// Scroll so that the top of the viewport is this many pixels into the content.
+    ScrollTo(f32),
+}
+
+pub struct ScrollContainerProps<E> {
+    pub id: Id,
+    pub child: E,
+    pub space: Space,
+    pub scrollbar_width: f32,
+    /// Pixels scrolled per unit of mouse wheel movement.
+    pub scroll_speed: f32,
+    pub scrollbar_color: Color,
+    /// Called with the new offset whenever it changes.
+    pub on_scroll: Option<Callback<f32>>,
+}
+
+impl<E> ScrollContainerProps<E> {
+    pub fn new(child: E) -> Self {
+        ScrollContainerProps {
+            id: next_id(),
+            child,
+            space: Space::Fill,
+            scrollbar_width: 10.,
+            scroll_speed: 20.,
+            scrollbar_color: GRAY,
+            on_scroll: None,
+        }
+    }
+}
+
+pub struct ScrollContainer<E> {
+    pub id: Id,
+    pub bounds: Bounds,
+    pub child: E,
+    pub space: Space,
+    pub scrollbar_width: f32,
+    pub scroll_speed: f32,
+    pub scrollbar_color: Color,
+    on_scroll: Option<Callback<f32>>,
+
+    tx: flume::Sender<Message>,
+    app: AppHandle,
+
+    offset: f32,
+    content_size: Size,
+    // Distance between the top of the thumb and the pointer while the thumb is being dragged.
+    drag: Option<f32>,
+}
+
+impl<E: Element> ScrollContainer<E> {
+    pub fn new(props: ScrollContainerProps<E>, ctx: &UIContext) -> Self {
+        ScrollContainer {
+            id: props.id,
+            bounds: Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            },
+            child: props.child,
+            space: props.space,
+            scrollbar_width: props.scrollbar_width,
+            scroll_speed: props.scroll_speed,
+            scrollbar_color: props.scrollbar_color,
+            on_scroll: props.on_scroll,
+            tx: ctx.tx.clone(),
+            app: ctx.app.clone(),
+            offset: 0.,
+            content_size: Size { w: 0., h: 0. },
+            drag: None,
+        }
+    }
+
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn max_offset(&self) -> f32 {
+        (self.content_size.h - self.bounds.size.h).max(0.)
+    }
+
+    /// Area the child is visible through, excluding the scrollbar.
+    pub fn viewport(&self) -> Bounds {
+        let scrollbar_width = if self.max_offset() > 0. {
+            self.scrollbar_width
+        } else {
+            0.
+        };
+        Bounds {
+            x: self.bounds.x,
+            y: self.bounds.y,
+            size: Size {
+                w: (self.bounds.size.w - scrollbar_width).max(0.),
+                h: self.bounds.size.h,
+            },
+        }
+    }
+
+    fn track(&self) -> Bounds {
+        Bounds {
+            x: self.bounds.x + self.bounds.size.w - self.scrollbar_width,
+            y: self.bounds.y,
+            size: Size {
+                w: self.scrollbar_width,
+                h: self.bounds.size.h,
+            },
+        }
+    }
+
+    fn thumb(&self) -> Bounds {
+        let track = self.track();
+        let h = track.size.h * (track.size.h / self.content_size.h).min(1.);
+        let progress = if self.max_offset() > 0. {
+            self.offset / self.max_offset()
+        } else {
+            0.
+        };
+        Bounds {
+            x: track.x,
+            y: track.y + (track.size.h - h) * progress,
+            size: Size { w: track.size.w, h },
+        }
+    }
+
+    fn scroll_to(&self, offset: f32) {
+        let offset = offset.clamp(0., self.max_offset());
+        if offset != self.offset {
+            self.tx
+                .send(Message {
+                    target: self.id,
+                    data: MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(offset)),
+                })
+                .unwrap();
+        }
+    }
+
+    fn wheel_offset(&self, wheel: Position) -> f32 {
+        (self.offset - wheel.y * self.scroll_speed).clamp(0., self.max_offset())
+    }
+
+    // Scrollables inside the viewport get the wheel first, this one only scrolls once the one
+    // under the pointer reached its end.
+    fn child_scrolls(&self, position: Position, wheel: Position) -> bool {
+        self.viewport().contains(position) && self.child.can_scroll(position, wheel)
+    }
+
+    fn layout_child(&mut self) {
+        let viewport = self.viewport();
+        self.child.set_bounds(Bounds {
+            x: viewport.x,
+            y: viewport.y - self.offset,
+            size: self.content_size,
+        });
+    }
+}
+
+impl<E: Element> Element for ScrollContainer<E> {
+    fn handle(&mut self, msg: &Message) {
+        if msg.target == self.id {
+            if let MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(offset)) =
+                &msg.data
+            {
+                let offset = offset.clamp(0., self.max_offset());
+                if offset != self.offset {
+                    self.offset = offset;
+                    self.layout_child();
+                    if let Some(on_scroll) = &self.on_scroll {
+                        on_scroll.call(&self.app, offset);
+                    }
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, state: &AppState) {
+        let hovered = self.bounds.contains(state.mouse_position);
+        // The pointer is on the scrollbar and nothing is drawn above it.
+        let on_scrollbar =
+            state.hovered == Some(self.id) && !self.viewport().contains(state.mouse_position);
+        if self.max_offset() > 0. {
+            let thumb = self.thumb();
+            match state.left_click {
+                KeyState::Pressed if on_scrollbar && thumb.contains(state.mouse_position) => {
+                    self.drag = Some(state.mouse_position.y - thumb.y);
+                }
+                KeyState::Pressed
+                    if on_scrollbar && self.track().contains(state.mouse_position) =>
+                {
+                    // Clicking the track pages towards the pointer.
+                    let page = if state.mouse_position.y < thumb.y {
+                        -self.bounds.size.h
+                    } else {
+                        self.bounds.size.h
+                    };
+                    self.scroll_to(self.offset + page);
+                }
+                KeyState::Held => {
+                    if let Some(grab) = self.drag {
+                        let track = self.track();
+                        let free = track.size.h - thumb.size.h;
+                        if free > 0. {
+                            let progress = (state.mouse_position.y - grab - track.y) / free;
+                            self.scroll_to(progress * self.max_offset());
+                        }
+                    }
+                }
+                KeyState::Released | KeyState::Unpressed => self.drag = None,
+                _ => (),
+            }
+
+            if hovered
+                && state.wheel.y != 0.
+                && !self.child_scrolls(state.mouse_position, state.wheel)
+            {
+                self.scroll_to(self.wheel_offset(state.wheel));
+            }
+        }
+
+        // Children can't be hovered or clicked through the parts of them that are scrolled out of view.
+        if self.viewport().contains(state.mouse_position) && self.drag.is_none() {
+            self.child.update(state);
+        } else {
+            self.child.update(&AppState {
+                mouse_position: Position {
+                    x: f32::NEG_INFINITY,
+                    y: f32::NEG_INFINITY,
+                },
+                hovered: None,
+                ..state.clone()
+            });
+        }
+    }
+
+    fn draw(&self, renderer: &dyn Renderer) {
+        renderer.push_clip_rect(self.viewport());
+        self.child.draw(renderer);
+        renderer.pop_clip_rect();
+
+        if self.max_offset() > 0. {
+            let track_color = Color {
+                a: self.scrollbar_color.a * 0.3,
+                ..self.scrollbar_color
+            };
+            renderer.draw_rectangle(self.track(), None, track_color);
+            renderer.draw_rectangle(self.thumb(), None, self.scrollbar_color);
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        let min = self.child.min_size();
+        self.bounds = Bounds {
+            x: bounds.x,
+            y: bounds.y,
+            size: match self.space {
+                Space::Fill => bounds.size,
+                Space::Minimize => min,
+            },
+        };
+
+        // Scrolling is vertical only, so the content keeps the viewport's width when it can.
+        self.content_size = Size { w: 0., h: min.h };
+        self.content_size.w = self.viewport().size.w.max(min.w);
+        self.offset = self.offset.clamp(0., self.max_offset());
+        self.layout_child();
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn min_size(&self) -> Size {
+        Size {
+            w: self.child.min_size().w + self.scrollbar_width,
+            h: 0.,
+        }
+    }
+
+    fn space(&self) -> Space {
+        self.space
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn hit_test(&self, position: Position) -> Option<Id> {
+        if self.viewport().contains(position) {
+            if let Some(hit) = self.child.hit_test(position) {
+                return Some(hit);
+            }
+        }
+        self.bounds.contains(position).then_some(self.id)
+    }
+
+    // Overlays escape the viewport's clip, but not for elements scrolled out of view.
+    fn draw_overlay(&self, renderer: &dyn Renderer, visible: Bounds) {
+        self.child
+            .draw_overlay(renderer, visible.intersect(self.viewport()));
+    }
+
+    fn hit_test_overlay(&self, position: Position, visible: Bounds) -> Option<Id> {
+        self.child
+            .hit_test_overlay(position, visible.intersect(self.viewport()))
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        vec![&self.child]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        vec![&mut self.child]
+    }
+
+    fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
+        v.push(self.bounds());
+        self.child.write_all_bounds(v);
+    }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.bounds.contains(position)
+            && (self.child_scrolls(position, wheel) || self.wheel_offset(wheel) != self.offset)
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        self.child.write_focusable(v);
+    }
+}
+
+#[test]
+pub fn test_scroll_container() {
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Item(Id, Bounds);
+    impl Element for Item {
+        fn update(&mut self, _state: &AppState) {}
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, bounds: Bounds) {
+            self.1 = bounds;
+        }
+        fn bounds(&self) -> Bounds {
+            self.1
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 40., h: 20. }
+        }
+        fn space(&self) -> Space {
+            Space::Minimize
+        }
+        fn id(&self) -> Id {
+            self.0
+        }
+    }
+
+    // Ten items of 20 pixels in a viewport of 50.
+    let ctx = UIContext::new();
+    let items = VListContainer::new(VListContainerProps {
+        children: (0..10)
+            .map(|_| {
+                Item(
+                    next_id(),
+                    Bounds {
+                        x: 0.,
+                        y: 0.,
+                        size: Size { w: 0., h: 0. },
+                    },
+                )
+            })
+            .collect(),
+        spacing: 0.,
+        ..Default::default()
+    });
+    let offsets = Rc::new(RefCell::new(Vec::new()));
+    let on_scroll = offsets.clone();
+    let mut scroll = ScrollContainer::new(
+        ScrollContainerProps {
+            on_scroll: Some(Callback::new(move |_, offset| {
+                on_scroll.borrow_mut().push(offset)
+            })),
+            ..ScrollContainerProps::new(items)
+        },
+        &ctx,
+    );
+    let step = |scroll: &mut ScrollContainer<_>, state: AppState| {
+        scroll.set_bounds(Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 100., h: 50. },
+        });
+        scroll.update(&AppState {
+            hovered: scroll.hit_test(state.mouse_position),
+            ..state
+        });
+        for msg in ctx.rx.drain() {
+            scroll.handle(&msg);
+        }
+    };
+
+    step(&mut scroll, AppState::default());
+    assert_eq!(scroll.max_offset(), 150.);
+    assert_eq!(scroll.viewport().size, Size { w: 90., h: 50. });
+
+    step(
+        &mut scroll,
+        AppState {
+            mouse_position: Position { x: 20., y: 10. },
+            wheel: Position { x: 0., y: -1. },
+            ..Default::default()
+        },
+    );
+    assert_eq!(scroll.offset(), 20.);
+    assert_eq!(scroll.child.bounds.y, -20.);
+
+    // Offsets are clamped to the content.
+    scroll.handle(&Message {
+        target: scroll.id,
+        data: MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(1000.)),
+    });
+    assert_eq!(scroll.offset(), 150.);
+    assert_eq!(scroll.child.bounds.y, -150.);
+
+    // The thumb is at the bottom, clicking the track above it pages up.
+    step(
+        &mut scroll,
+        AppState {
+            mouse_position: Position { x: 95., y: 5. },
+            left_click: KeyState::Pressed,
+            ..Default::default()
+        },
+    );
+    assert_eq!(scroll.offset(), 100.);
+    step(&mut scroll, AppState::default());
+
+    // Dragging the thumb, which is 12.5 high and 25 down the track, to the top.
+    step(
+        &mut scroll,
+        AppState {
+            mouse_position: Position { x: 95., y: 30. },
+            left_click: KeyState::Pressed,
+            ..Default::default()
+        },
+    );
+    assert_eq!(scroll.offset(), 100.);
+    step(
+        &mut scroll,
+        AppState {
+            mouse_position: Position { x: 95., y: 5. },
+            left_click: KeyState::Held,
+            ..Default::default()
+        },
+    );
+    assert_eq!(scroll.offset(), 0.);
+    assert_eq!(*offsets.borrow(), [20., 150., 100., 0.]);
+}
+
+#[test]
+pub fn test_nested_scroll_containers() {
+    use crate::grid_container::{GridContainer, GridContainerProps, GridEntry, Track};
+    use crate::router::Router;
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    struct Item(Id, Bounds);
+    impl Element for Item {
+        fn update(&mut self, _state: &AppState) {}
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, bounds: Bounds) {
+            self.1 = bounds;
+        }
+        fn bounds(&self) -> Bounds {
+            self.1
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 40., h: 20. }
+        }
+        fn space(&self) -> Space {
+            Space::Minimize
+        }
+        fn id(&self) -> Id {
+            self.0
+        }
+    }
+
+    // Two scrollables of ten 20 pixel items, each 50 high, in an outer one of 50.
+    let ctx = UIContext::new();
+    let inner = |row| GridEntry {
+        row,
+        column: 0,
+        row_span: 1,
+        column_span: 1,
+        child: ScrollContainer::new(
+            ScrollContainerProps::new(VListContainer::new(VListContainerProps {
+                children: (0..10)
+                    .map(|_| {
+                        Item(
+                            next_id(),
+                            Bounds {
+                                x: 0.,
+                                y: 0.,
+                                size: Size { w: 0., h: 0. },
+                            },
+                        )
+                    })
+                    .collect(),
+                spacing: 0.,
+                ..Default::default()
+            })),
+            &ctx,
+        ),
+    };
+    let grid = GridContainer::new(GridContainerProps {
+        rows: vec![Track::Fixed(50.), Track::Fixed(50.)],
+        columns: vec![Track::Fraction(1.)],
+        entries: vec![inner(0), inner(1)],
+        spacing: 0.,
+        ..Default::default()
+    });
+    let mut outer = ScrollContainer::new(ScrollContainerProps::new(grid), &ctx);
+    let mut router = Router::new();
+    let mut wheel = |outer: &mut ScrollContainer<_>, y| {
+        outer.set_bounds(Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 100., h: 50. },
+        });
+        outer.update(&AppState {
+            mouse_position: Position { x: 20., y: 10. },
+            wheel: Position { x: 0., y },
+            ..Default::default()
+        });
+        for msg in ctx.rx.drain() {
+            router.dispatch(outer, &msg);
+        }
+    };
+
+    wheel(&mut outer, 0.);
+    assert_eq!(outer.max_offset(), 50.);
+
+    // Only the innermost scrollable under the pointer takes the wheel.
+    wheel(&mut outer, -1.);
+    assert_eq!(outer.child.entries[0].child.offset(), 20.);
+    assert_eq!(outer.offset(), 0.);
+
+    // Once it reached its end the outer one scrolls.
+    let scroll_to_end = Message {
+        target: outer.child.entries[0].child.id,
+        data: MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(150.)),
+    };
+    Router::new().dispatch(&mut outer, &scroll_to_end);
+    wheel(&mut outer, -1.);
+    assert_eq!(outer.child.entries[0].child.offset(), 150.);
+    assert_eq!(outer.offset(), 20.);
+
+    // Scrolling back up is the inner one's again.
+    wheel(&mut outer, 1.);
+    assert_eq!(outer.child.entries[0].child.offset(), 130.);
+    assert_eq!(outer.offset(), 20.);
+}
+
+#[test]
+pub fn test_scroll_container_overlays() {
+    use crate::button::{Button, ButtonProps};
+    use crate::expandable_button::{
+        ExpandableButton, ExpandableButtonMessage, ExpandableButtonProps,
+    };
+    use crate::recording_renderer::{MonospaceTextMeasurer, RecordingRenderer};
+    use crate::types::{draw_with_overlays, hit_test_with_overlays};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+    use std::rc::Rc;
+
+    // Only has overlay content, drawn and hit over its own bounds.
+    struct Tip(Id, Bounds, String);
+    impl Element for Tip {
+        fn update(&mut self, _state: &AppState) {}
+        fn draw(&self, _renderer: &dyn Renderer) {}
+        fn set_bounds(&mut self, bounds: Bounds) {
+            self.1 = bounds;
+        }
+        fn bounds(&self) -> Bounds {
+            self.1
+        }
+        fn min_size(&self) -> Size {
+            Size { w: 40., h: 20. }
+        }
+        fn space(&self) -> Space {
+            Space::Minimize
+        }
+        fn id(&self) -> Id {
+            self.0
+        }
+        fn draw_overlay(&self, renderer: &dyn Renderer, _visible: Bounds) {
+            renderer.draw_text(self.1, &self.2, None, 16, WHITE);
+        }
+        fn hit_test_overlay(&self, position: Position, _visible: Bounds) -> Option<Id> {
+            self.1.contains(position).then_some(self.0)
+        }
+    }
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let bounds = Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 50. },
+    };
+    let tips = (0..10)
+        .map(|idx| Tip(next_id(), bounds, format!("tip {}", idx)))
+        .collect();
+    let mut scroll = ScrollContainer::new(
+        ScrollContainerProps::new(VListContainer::new(VListContainerProps {
+            children: tips,
+            spacing: 0.,
+            ..Default::default()
+        })),
+        &ctx,
+    );
+    scroll.set_bounds(bounds);
+    let renderer = RecordingRenderer::new();
+    let first = scroll.child.children[0].1;
+    let on_first = Position {
+        x: first.x + 1.,
+        y: first.y + 1.,
+    };
+
+    // Overlays of elements scrolled out of view are neither drawn nor hit.
+    draw_with_overlays(&scroll, &renderer);
+    assert_eq!(renderer.texts(), ["tip 0", "tip 1", "tip 2"]);
+    assert_eq!(
+        hit_test_with_overlays(&scroll, on_first),
+        Some(scroll.child.children[0].0)
+    );
+    scroll.handle(&Message {
+        target: scroll.id,
+        data: MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(1000.)),
+    });
+    renderer.clear();
+    draw_with_overlays(&scroll, &renderer);
+    assert_eq!(renderer.texts(), ["tip 7", "tip 8", "tip 9"]);
+    assert_eq!(
+        hit_test_with_overlays(&scroll, on_first),
+        Some(scroll.child.children[7].0)
+    );
+
+    // The same goes for the list of an expanded button.
+    let dropdowns = (0..5)
+        .map(|idx| {
+            ExpandableButton::new(
+                ExpandableButtonProps {
+                    id: next_id(),
+                    main: ButtonProps {
+                        text: "Menu".into(),
+                        ..Default::default()
+                    },
+                    list: Button::new(
+                        ButtonProps {
+                            text: format!("Item {}", idx),
+                            ..Default::default()
+                        },
+                        &ctx,
+                    ),
+                    on_expand: None,
+                    on_fold: None,
+                },
+                &ctx,
+            )
+        })
+        .collect();
+    let mut scroll = ScrollContainer::new(
+        ScrollContainerProps::new(VListContainer::new(VListContainerProps {
+            children: dropdowns,
+            spacing: 0.,
+            ..Default::default()
+        })),
+        &ctx,
+    );
+    let first = &mut scroll.child.children[0];
+    first.handle(&Message {
+        target: first.id(),
+        data: MessageData::ExpandableButton(ExpandableButtonMessage::Expand),
+    });
+    for _ in 0..3 {
+        scroll.set_bounds(bounds);
+        scroll.update(&AppState {
+            dt: 1.,
+            ..Default::default()
+        });
+    }
+    let item = scroll.child.children[0].children()[1];
+    let on_item = Position {
+        x: item.bounds().x + 1.,
+        y: item.bounds().y + 1.,
+    };
+    assert_eq!(hit_test_with_overlays(&scroll, on_item), Some(item.id()));
+    renderer.clear();
+    draw_with_overlays(&scroll, &renderer);
+    assert!(renderer.texts().contains(&"Item 0".to_owned()));
+
+    scroll.handle(&Message {
+        target: scroll.id,
+        data: MessageData::ScrollContainer(ScrollContainerMessage::ScrollTo(1000.)),
+    });
+    let item = scroll.child.children[0].children()[1].id();
+    assert_ne!(hit_test_with_overlays(&scroll, on_item), Some(item));
+    renderer.clear();
+    draw_with_overlays(&scroll, &renderer);
+    assert!(!renderer.texts().contains(&"Item 0".to_owned()));
+}
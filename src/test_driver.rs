@@ -0,0 +1,257 @@
+use crate::{
+    app::App,
+    input::InputTracker,
+    recording_renderer::{DrawCommand, RecordingRenderer},
+    types::{
+        hit_test_with_overlays, Bounds, Element, Id, Message, Position, Size, Subscription,
+        UIContext,
+    },
+};
+use macroquad::prelude::*;
+
+/// Frame time used when advancing time.
+pub const FRAME_TIME: f32 = 1. / 60.;
+
+/// Runs an element tree without a window, driven by synthetic input, for integration tests.
+///
+/// Frames go through `App::step`, so layout, updates and message routing happen exactly like
+/// in `App::run`. Build the elements with a `UIContext` using `MonospaceTextMeasurer`, since
+/// measuring text with macroquad needs a window.
+pub struct TestDriver<E> {
+    pub app: App<E, RecordingRenderer>,
+    pub input: InputTracker,
+    pub screen: Size,
+
+    all: Subscription<Message>,
+    messages: Vec<Message>,
+    // Draw commands of the last frame.
+    commands: Vec<DrawCommand>,
+}
+
+impl<E: Element> TestDriver<E> {
+    pub fn new(root: E, ctx: UIContext) -> Self {
+        let mut app = App::new(root, ctx, RecordingRenderer::new());
+        let all = app.observer.observe_all();
+        TestDriver {
+            app,
+            input: InputTracker::new(),
+            screen: Size { w: 800., h: 600. },
+            all,
+            messages: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn root(&self) -> &E {
+        &self.app.root
+    }
+
+    /// Runs a single frame that took `dt` seconds.
+    pub fn frame(&mut self, dt: f32) {
+        let state = self.input.frame(dt);
+        self.app.step(self.screen, state, &mut |_, _| ());
+        self.messages.extend(self.all.drain());
+        self.commands = self.app.renderer.take();
+    }
+
+    /// Runs frames of `FRAME_TIME` until `seconds` have passed.
+    pub fn advance(&mut self, seconds: f32) {
+        let mut left = seconds;
+        while left > 0. {
+            self.frame(FRAME_TIME.min(left));
+            left -= FRAME_TIME;
+        }
+    }
+
+    pub fn move_to(&mut self, position: Position) {
+        self.input.set_mouse_position(position);
+        self.frame(0.);
+    }
+
+    /// Moves the pointer to the center of the element with the given id. Returns false if it
+    /// isn't part of the tree.
+    pub fn move_to_element(&mut self, id: Id) -> bool {
+        match self.app.root.find(id) {
+            Some(element) => {
+                self.move_to(element.bounds().center());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Presses and releases the left mouse button at the current pointer position.
+    pub fn click(&mut self) {
+        self.input.mouse_down(MouseButton::Left);
+        self.input.mouse_up(MouseButton::Left);
+        self.frame(0.);
+        self.frame(0.);
+    }
+
+    /// Clicks the topmost element showing `text`. Returns false if no such text was drawn.
+    pub fn click_text(&mut self, text: &str) -> bool {
+        match self.text_position(text) {
+            Some(position) => {
+                self.move_to(position);
+                self.click();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Presses and releases `key`.
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.input.key_down(key);
+        self.input.key_up(key);
+        self.frame(0.);
+        self.frame(0.);
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.input.type_char(c);
+        }
+        self.frame(0.);
+    }
+
+    /// Every message sent since the driver was created or the last `take_messages`.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.messages)
+    }
+
+    /// What was drawn in the last frame.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    pub fn find(&self, id: Id) -> Option<&dyn Element> {
+        self.app.root.find(id)
+    }
+
+    /// The topmost element at the place `text` was drawn in the last frame.
+    pub fn find_by_text(&self, text: &str) -> Option<Id> {
+        hit_test_with_overlays(&self.app.root, self.text_position(text)?)
+    }
+
+    /// Whether `text` was drawn in the last frame and isn't clipped away.
+    pub fn is_text_visible(&self, text: &str) -> bool {
+        self.text_position(text).is_some()
+    }
+
+    // Middle of the last visible occurrence of `text`, the one drawn on top.
+    fn text_position(&self, text: &str) -> Option<Position> {
+        let screen = Bounds {
+            x: 0.,
+            y: 0.,
+            size: self.screen,
+        };
+        let mut clips = vec![screen];
+        let mut found = None;
+        for command in &self.commands {
+            match command {
+                DrawCommand::PushClip(bounds) => clips.push(bounds.intersect(screen)),
+                DrawCommand::PopClip => {
+                    clips.pop();
+                }
+                DrawCommand::Text {
+                    bounds,
+                    text: drawn,
+                    font,
+                    font_size,
+                    ..
+                } if drawn == text => {
+                    // Text is drawn from its baseline.
+                    let size = self.app.ctx.measurer.measure_text(text, *font, *font_size);
+                    let position = Position {
+                        x: bounds.x + size.w / 2.,
+                        y: bounds.y - size.h / 2.,
+                    };
+                    let clip = clips.last().copied().unwrap_or(screen);
+                    if clip.size.w > 0. && clip.size.h > 0. && clip.contains(position) {
+                        found = Some(position);
+                    }
+                }
+                _ => (),
+            }
+        }
+        found
+    }
+}
+
+#[test]
+pub fn test_driver_expandable_button() {
+    use crate::anchor_container::{AnchorContainer, AnchorContainerProps, AnchorEntry};
+    use crate::button::{Button, ButtonMessage, ButtonProps};
+    use crate::expandable_button::{ExpandableButton, ExpandableButtonProps};
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::types::{next_id, Anchor, AnchorX, AnchorY, MessageData, PreserveRatio, Scale};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let top_left = Anchor {
+        x: AnchorX::Left,
+        y: AnchorY::Top,
+    };
+    let expandable = ExpandableButton::new(
+        ExpandableButtonProps {
+            id: next_id(),
+            main: ButtonProps {
+                text: "Expandable".into(),
+                anchor: top_left,
+                preserve_ratio: PreserveRatio::Height(7.),
+                ..Default::default()
+            },
+            list: VListContainer::new(VListContainerProps {
+                children: vec![Button::new(
+                    ButtonProps {
+                        text: "Item".into(),
+                        anchor: top_left,
+                        ..Default::default()
+                    },
+                    &ctx,
+                )],
+                ..Default::default()
+            }),
+            on_expand: None,
+            on_fold: None,
+        },
+        &ctx,
+    );
+    let root = AnchorContainer::new(AnchorContainerProps {
+        entries: vec![AnchorEntry {
+            scale: Scale { x: 0.5, y: 1. },
+            anchor: top_left,
+            child: expandable,
+        }],
+        ..Default::default()
+    });
+    let mut driver = TestDriver::new(root, ctx);
+    driver.frame(0.);
+    assert!(driver.is_text_visible("Expandable"));
+    assert!(!driver.is_text_visible("Item"));
+
+    let main = driver.find_by_text("Expandable").unwrap();
+    assert!(driver.find(main).is_some());
+    assert!(driver.move_to_element(main));
+    driver.click();
+    driver.advance(1.);
+    assert!(driver.is_text_visible("Item"));
+    assert!(driver
+        .messages()
+        .iter()
+        .any(|msg| msg.target == main
+            && matches!(msg.data, MessageData::Button(ButtonMessage::Click))));
+
+    driver.take_messages();
+    assert!(driver.click_text("Item"));
+    assert!(driver
+        .take_messages()
+        .iter()
+        .any(|msg| matches!(msg.data, MessageData::Button(ButtonMessage::Click))));
+}
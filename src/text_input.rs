@@ -0,0 +1,670 @@
+use crate::{
+    callback::{AppHandle, Callback},
+    focus::{FocusMessage, Focusable},
+    types::{
+        next_id, ActionState, AppState, Bounds, Element, Id, KeyState, Message, MessageData,
+        Renderer, Size, Space, TextMeasurer, UIContext, DEFAULT_FONT_SIZE,
+    },
+};
+use macroquad::prelude::*;
+use std::rc::Rc;
+
+const PADDING: f32 = 5.;
+/// Seconds a key has to be held before it starts repeating.
+const REPEAT_DELAY: f32 = 0.5;
+/// Seconds between repeats of a held key.
+const REPEAT_INTERVAL: f32 = 0.05;
+const BLINK_INTERVAL: f32 = 0.5;
+
+#[derive(Debug, Clone)]
+pub enum TextInputMessage {
+    /// The text was edited, contains the new text.
+    Changed(String),
+    /// Enter was pressed while focused, contains the current text.
+    Submitted(String),
+}
+
+#[derive(Debug)]
+pub struct TextInputProps {
+    pub id: Id,
+    pub text: String,
+    pub placeholder: String,
+    pub space: Space,
+    pub color: Color,
+    pub text_color: Color,
+    pub on_change: Option<Callback<String>>,
+    pub on_submit: Option<Callback<String>>,
+}
+
+impl Default for TextInputProps {
+    fn default() -> Self {
+        Self {
+            id: next_id(),
+            text: String::new(),
+            placeholder: String::new(),
+            space: Space::Fill,
+            color: LIGHTGRAY,
+            text_color: BLACK,
+            on_change: None,
+            on_submit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+impl Edit {
+    fn from_key(key: KeyCode) -> Option<Edit> {
+        match key {
+            KeyCode::Backspace => Some(Edit::Backspace),
+            KeyCode::Delete => Some(Edit::Delete),
+            KeyCode::Left => Some(Edit::Left),
+            KeyCode::Right => Some(Edit::Right),
+            KeyCode::Home => Some(Edit::Home),
+            KeyCode::End => Some(Edit::End),
+            _ => None,
+        }
+    }
+}
+
+pub struct TextInput {
+    pub id: Id,
+    pub bounds: Bounds,
+    pub space: Space,
+    pub placeholder: String,
+    color: Color,
+    text_color: Color,
+    text: String,
+    on_change: Option<Callback<String>>,
+    on_submit: Option<Callback<String>>,
+
+    tx: flume::Sender<Message>,
+    measurer: Rc<dyn TextMeasurer>,
+    app: AppHandle,
+
+    focused: bool,
+    // The text changed since the last layout, which changes `min_size`.
+    dirty: bool,
+    // Caret and selection anchor, both counted in chars. The selection spans between the two.
+    caret: usize,
+    selection_anchor: usize,
+    blink: f32,
+    // Key currently being held and for how long.
+    repeat: Option<(KeyCode, f32)>,
+}
+
+impl TextInput {
+    pub fn new(props: TextInputProps, ctx: &UIContext) -> Self {
+        let len = props.text.chars().count();
+        TextInput {
+            id: props.id,
+            bounds: Bounds {
+                x: 0.,
+                y: 0.,
+                size: Size { w: 0., h: 0. },
+            },
+            space: props.space,
+            placeholder: props.placeholder,
+            color: props.color,
+            text_color: props.text_color,
+            text: props.text,
+            on_change: props.on_change,
+            on_submit: props.on_submit,
+            tx: ctx.tx.clone(),
+            measurer: ctx.measurer.clone(),
+            app: ctx.app.clone(),
+            focused: false,
+            dirty: false,
+            caret: len,
+            selection_anchor: len,
+            blink: 0.,
+            repeat: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Selected range in chars, empty when nothing is selected.
+    pub fn selection(&self) -> std::ops::Range<usize> {
+        self.caret.min(self.selection_anchor)..self.caret.max(self.selection_anchor)
+    }
+
+    fn set_focus(&mut self, state: ActionState) {
+        match state {
+            ActionState::Start => {
+                self.focused = true;
+                self.blink = 0.;
+            }
+            ActionState::End => {
+                self.focused = false;
+                self.selection_anchor = self.caret;
+                self.repeat = None;
+            }
+        }
+    }
+
+    fn send(&self, msg: TextInputMessage) {
+        self.tx
+            .send(Message {
+                target: self.id,
+                data: MessageData::TextInput(msg),
+            })
+            .unwrap();
+    }
+
+    fn send_focus(&self, state: ActionState) {
+        self.tx
+            .send(Message {
+                target: self.id,
+                data: MessageData::Focus(FocusMessage::Focus(state)),
+            })
+            .unwrap();
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(idx, _)| idx)
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn move_caret(&mut self, to: usize, select: bool) {
+        self.caret = to.min(self.len());
+        if !select {
+            self.selection_anchor = self.caret;
+        }
+    }
+
+    // Removes the selected text, returns false if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        if selection.is_empty() {
+            return false;
+        }
+        let range = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.text.replace_range(range, "");
+        self.caret = selection.start;
+        self.selection_anchor = selection.start;
+        true
+    }
+
+    fn insert(&mut self, c: char) {
+        self.delete_selection();
+        let idx = self.byte_index(self.caret);
+        self.text.insert(idx, c);
+        self.move_caret(self.caret + 1, false);
+    }
+
+    // Applies an edit, returns whether the text changed.
+    fn apply(&mut self, edit: Edit, select: bool) -> bool {
+        match edit {
+            Edit::Backspace => {
+                if self.delete_selection() {
+                    return true;
+                }
+                if self.caret == 0 {
+                    return false;
+                }
+                let idx = self.byte_index(self.caret - 1);
+                self.text.remove(idx);
+                self.move_caret(self.caret - 1, false);
+                true
+            }
+            Edit::Delete => {
+                if self.delete_selection() {
+                    return true;
+                }
+                if self.caret == self.len() {
+                    return false;
+                }
+                let idx = self.byte_index(self.caret);
+                self.text.remove(idx);
+                true
+            }
+            Edit::Left => {
+                let selection = self.selection();
+                if !select && !selection.is_empty() {
+                    self.move_caret(selection.start, false);
+                } else {
+                    self.move_caret(self.caret.saturating_sub(1), select);
+                }
+                false
+            }
+            Edit::Right => {
+                let selection = self.selection();
+                if !select && !selection.is_empty() {
+                    self.move_caret(selection.end, false);
+                } else {
+                    self.move_caret(self.caret + 1, select);
+                }
+                false
+            }
+            Edit::Home => {
+                self.move_caret(0, select);
+                false
+            }
+            Edit::End => {
+                self.move_caret(self.len(), select);
+                false
+            }
+        }
+    }
+
+    fn line_height(&self) -> f32 {
+        self.measurer.measure_text("|", None, DEFAULT_FONT_SIZE).h
+    }
+
+    fn text_position(&self) -> (f32, f32) {
+        let height = self.line_height();
+        (
+            self.bounds.x + PADDING,
+            self.bounds.y + self.bounds.size.h / 2. + height / 2.,
+        )
+    }
+
+    fn prefix_width(&self, chars: usize) -> f32 {
+        let prefix = &self.text[..self.byte_index(chars)];
+        self.measurer
+            .measure_text(prefix, None, DEFAULT_FONT_SIZE)
+            .w
+    }
+
+    // Char index closest to the horizontal position `x`.
+    fn caret_at(&self, x: f32) -> usize {
+        let x = x - self.text_position().0;
+        (0..=self.len())
+            .min_by(|&a, &b| {
+                let a = (self.prefix_width(a) - x).abs();
+                let b = (self.prefix_width(b) - x).abs();
+                a.total_cmp(&b)
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl Element for TextInput {
+    fn handle(&mut self, msg: &Message) {
+        if msg.target == self.id {
+            match &msg.data {
+                MessageData::Focus(FocusMessage::Focus(state)) => self.set_focus(*state),
+                MessageData::TextInput(TextInputMessage::Changed(text)) => {
+                    if let Some(on_change) = &self.on_change {
+                        on_change.call(&self.app, text.clone());
+                    }
+                }
+                MessageData::TextInput(TextInputMessage::Submitted(text)) => {
+                    if let Some(on_submit) = &self.on_submit {
+                        on_submit.call(&self.app, text.clone());
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn update(&mut self, state: &AppState) {
+        self.blink = (self.blink + state.dt) % (BLINK_INTERVAL * 2.);
+
+        if state.left_click == KeyState::Pressed {
+            if state.hovered == Some(self.id) {
+                let select = self.focused && state.modifiers.shift;
+                self.move_caret(self.caret_at(state.mouse_position.x), select);
+                if !self.focused {
+                    self.send_focus(ActionState::Start);
+                }
+            } else if self.focused {
+                self.send_focus(ActionState::End);
+            }
+        }
+
+        if !self.focused {
+            return;
+        }
+
+        let select = state.modifiers.shift;
+        let mut changed = false;
+        for key in &state.keys {
+            if key.key == KeyCode::Enter && key.state == KeyState::Pressed {
+                self.send(TextInputMessage::Submitted(self.text.clone()));
+            }
+            if key.key == KeyCode::Escape && key.state == KeyState::Pressed {
+                self.send_focus(ActionState::End);
+            }
+
+            let edit = match Edit::from_key(key.key) {
+                Some(edit) => edit,
+                None => continue,
+            };
+            let repeat = match key.state {
+                KeyState::Pressed => {
+                    self.repeat = Some((key.key, 0.));
+                    true
+                }
+                KeyState::Held => match &mut self.repeat {
+                    Some((repeat_key, held)) if *repeat_key == key.key => {
+                        *held += state.dt;
+                        if *held >= REPEAT_DELAY {
+                            *held -= REPEAT_INTERVAL;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    _ => false,
+                },
+                KeyState::Released | KeyState::Unpressed => {
+                    if matches!(self.repeat, Some((repeat_key, _)) if repeat_key == key.key) {
+                        self.repeat = None;
+                    }
+                    false
+                }
+            };
+            if repeat {
+                changed |= self.apply(edit, select);
+                self.blink = 0.;
+            }
+        }
+
+        for &c in &state.input {
+            if !c.is_control() {
+                self.insert(c);
+                self.blink = 0.;
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+            self.send(TextInputMessage::Changed(self.text.clone()));
+        }
+    }
+
+    fn draw(&self, renderer: &dyn Renderer) {
+        renderer.draw_rectangle(self.bounds, None, self.color);
+        renderer.push_clip_rect(self.bounds);
+
+        let (x, y) = self.text_position();
+        let height = self.line_height();
+        let selection = self.selection();
+        if self.focused && !selection.is_empty() {
+            let start = self.prefix_width(selection.start);
+            let end = self.prefix_width(selection.end);
+            renderer.draw_rectangle(
+                Bounds {
+                    x: x + start,
+                    y: y - height,
+                    size: Size {
+                        w: end - start,
+                        h: height,
+                    },
+                },
+                None,
+                SKYBLUE,
+            );
+        }
+
+        if self.text.is_empty() && !self.focused {
+            let size = Size { w: 0., h: 0. };
+            renderer.draw_text(
+                Bounds { x, y, size },
+                &self.placeholder,
+                None,
+                DEFAULT_FONT_SIZE,
+                GRAY,
+            );
+        } else {
+            let size = Size { w: 0., h: 0. };
+            renderer.draw_text(
+                Bounds { x, y, size },
+                &self.text,
+                None,
+                DEFAULT_FONT_SIZE,
+                self.text_color,
+            );
+        }
+
+        if self.focused && self.blink < BLINK_INTERVAL {
+            renderer.draw_rectangle(
+                Bounds {
+                    x: x + self.prefix_width(self.caret),
+                    y: y - height,
+                    size: Size { w: 1., h: height },
+                },
+                None,
+                self.text_color,
+            );
+        }
+
+        renderer.pop_clip_rect();
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
+        self.bounds = Bounds {
+            x: bounds.x,
+            y: bounds.y,
+            size: match self.space {
+                Space::Fill => bounds.size,
+                Space::Minimize => self.min_size(),
+            },
+        };
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn min_size(&self) -> Size {
+        let text = self
+            .measurer
+            .measure_text(&self.text, None, DEFAULT_FONT_SIZE);
+        Size {
+            w: text.w + PADDING * 2.,
+            h: self.line_height() + PADDING * 2.,
+        }
+    }
+
+    fn space(&self) -> Space {
+        self.space
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.dirty
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        v.push(Focusable {
+            id: self.id,
+            bounds: self.bounds,
+            text_entry: true,
+        });
+    }
+}
+
+#[test]
+pub fn test_text_input_editing() {
+    let ctx = UIContext::new();
+    let mut input = TextInput::new(
+        TextInputProps {
+            text: "hello".into(),
+            ..Default::default()
+        },
+        &ctx,
+    );
+
+    input.apply(Edit::Left, false);
+    input.insert('!');
+    assert_eq!(input.text(), "hell!o");
+
+    input.apply(Edit::Home, false);
+    input.apply(Edit::Right, true);
+    input.apply(Edit::Right, true);
+    assert_eq!(input.selection(), 0..2);
+    input.insert('J');
+    assert_eq!(input.text(), "Jll!o");
+    assert_eq!(input.selection(), 1..1);
+
+    assert!(input.apply(Edit::Backspace, false));
+    assert!(!input.apply(Edit::Backspace, false));
+    input.apply(Edit::End, false);
+    assert!(!input.apply(Edit::Delete, false));
+    input.apply(Edit::Left, false);
+    assert!(input.apply(Edit::Delete, false));
+    assert_eq!(input.text(), "ll!");
+}
+
+#[test]
+pub fn test_text_input_update() {
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::types::{Key, Modifiers, Position};
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let mut input = TextInput::new(
+        TextInputProps {
+            text: "hello".into(),
+            ..Default::default()
+        },
+        &ctx,
+    );
+    let key = |key, state| Key { key, state };
+    // Runs a frame and returns the messages the input sent, after handling them.
+    let frame = |input: &mut TextInput, state: AppState| {
+        input.update(&state);
+        let messages: Vec<_> = ctx.rx.drain().collect();
+        for msg in &messages {
+            input.handle(msg);
+        }
+        messages.into_iter().map(|msg| msg.data).collect::<Vec<_>>()
+    };
+
+    // Unfocused inputs ignore the keyboard.
+    let typed = AppState {
+        input: vec!['!'],
+        ..Default::default()
+    };
+    assert!(frame(&mut input, typed.clone()).is_empty());
+
+    // Clicking focuses the input and puts the caret at the closest char, chars are 10 wide.
+    input.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 200., h: 30. },
+    });
+    let click = AppState {
+        mouse_position: Position {
+            x: PADDING + 18.,
+            y: 10.,
+        },
+        left_click: KeyState::Pressed,
+        hovered: Some(input.id),
+        ..Default::default()
+    };
+    let sent = frame(&mut input, click);
+    assert!(matches!(
+        &sent[..],
+        [MessageData::Focus(FocusMessage::Focus(ActionState::Start))]
+    ));
+    assert!(input.focused());
+    assert_eq!(input.selection(), 2..2);
+
+    let end = AppState {
+        keys: vec![key(KeyCode::End, KeyState::Pressed)],
+        ..Default::default()
+    };
+    assert!(frame(&mut input, end).is_empty());
+    assert!(!input.needs_layout());
+    let sent = frame(&mut input, typed);
+    assert!(matches!(
+        &sent[..],
+        [MessageData::TextInput(TextInputMessage::Changed(text))] if text == "hello!"
+    ));
+    // The longer text needs a new layout.
+    assert!(input.needs_layout());
+    input.set_bounds(input.bounds);
+    assert!(!input.needs_layout());
+
+    let enter = AppState {
+        keys: vec![key(KeyCode::Enter, KeyState::Pressed)],
+        ..Default::default()
+    };
+    let sent = frame(&mut input, enter);
+    assert!(matches!(
+        &sent[..],
+        [MessageData::TextInput(TextInputMessage::Submitted(text))] if text == "hello!"
+    ));
+
+    // A held key repeats after REPEAT_DELAY, then every REPEAT_INTERVAL.
+    let backspace = |state, dt| AppState {
+        keys: vec![key(KeyCode::Backspace, state)],
+        dt,
+        ..Default::default()
+    };
+    assert_eq!(frame(&mut input, backspace(KeyState::Pressed, 0.)).len(), 1);
+    assert!(frame(&mut input, backspace(KeyState::Held, REPEAT_DELAY / 2.)).is_empty());
+    assert_eq!(input.text(), "hello");
+    assert_eq!(
+        frame(&mut input, backspace(KeyState::Held, REPEAT_DELAY / 2.)).len(),
+        1
+    );
+    assert!(frame(&mut input, backspace(KeyState::Held, REPEAT_INTERVAL / 2.)).is_empty());
+    assert_eq!(
+        frame(&mut input, backspace(KeyState::Held, REPEAT_INTERVAL)).len(),
+        1
+    );
+    assert_eq!(input.text(), "hel");
+    frame(&mut input, backspace(KeyState::Released, 0.));
+
+    // Shift extends the selection, typing replaces it.
+    let shift_left = AppState {
+        keys: vec![key(KeyCode::Left, KeyState::Pressed)],
+        modifiers: Modifiers {
+            shift: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(frame(&mut input, shift_left.clone()).is_empty());
+    frame(&mut input, shift_left);
+    assert_eq!(input.selection(), 1..3);
+    frame(
+        &mut input,
+        AppState {
+            input: vec!['y'],
+            ..Default::default()
+        },
+    );
+    assert_eq!(input.text(), "hy");
+
+    let escape = AppState {
+        keys: vec![key(KeyCode::Escape, KeyState::Pressed)],
+        ..Default::default()
+    };
+    let sent = frame(&mut input, escape);
+    assert!(matches!(
+        &sent[..],
+        [MessageData::Focus(FocusMessage::Focus(ActionState::End))]
+    ));
+    assert!(!input.focused());
+}
@@ -1,12 +1,26 @@
 use macroquad::prelude::*;
 use std::{
+    any::Any,
+    cell::RefCell,
     collections::HashMap,
-    sync::atomic::{AtomicU32, Ordering},
+    fmt,
+    rc::{Rc, Weak},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
 };
 
-use crate::{button::ButtonMessage, expandable_button::ExpandableButtonMessage};
+use crate::{
+    button::ButtonMessage,
+    callback::AppHandle,
+    expandable_button::ExpandableButtonMessage,
+    focus::{FocusMessage, Focusable},
+    scroll_container::ScrollContainerMessage,
+    text_input::TextInputMessage,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Position {
     pub x: f32,
     pub y: f32,
@@ -26,6 +40,51 @@ impl Bounds {
             && position.x <= self.x + self.size.w
             && position.y <= self.y + self.size.h
     }
+
+    /// Overlapping area of both bounds, empty (but positioned inside `self`) if they don't overlap.
+    pub fn intersect(&self, other: Bounds) -> Bounds {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.size.w).min(other.x + other.size.w);
+        let bottom = (self.y + self.size.h).min(other.y + other.size.h);
+        Bounds {
+            x: x.min(self.x + self.size.w),
+            y: y.min(self.y + self.size.h),
+            size: Size {
+                w: (right - x).max(0.),
+                h: (bottom - y).max(0.),
+            },
+        }
+    }
+
+    /// Whether both bounds share any point, edges included like in `contains`.
+    pub fn overlaps(&self, other: Bounds) -> bool {
+        self.x <= other.x + other.size.w
+            && other.x <= self.x + self.size.w
+            && self.y <= other.y + other.size.h
+            && other.y <= self.y + self.size.h
+    }
+
+    /// Smallest bounds containing both bounds.
+    pub fn union(&self, other: Bounds) -> Bounds {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Bounds {
+            x,
+            y,
+            size: Size {
+                w: (self.x + self.size.w).max(other.x + other.size.w) - x,
+                h: (self.y + self.size.h).max(other.y + other.size.h) - y,
+            },
+        }
+    }
+
+    pub fn center(&self) -> Position {
+        Position {
+            x: self.x + self.size.w / 2.,
+            y: self.y + self.size.h / 2.,
+        }
+    }
 }
 
 #[test]
@@ -46,6 +105,42 @@ pub fn test_bounds() {
     assert!(!bounds.contains(Position { x: 12., y: 21. }));
 }
 
+#[test]
+pub fn test_clip_stack() {
+    let mut clip = ClipStack::default();
+    assert_eq!(clip.current(), None);
+
+    let outer = Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 100. },
+    };
+    assert_eq!(clip.push(outer), outer);
+    let inner = clip.push(Bounds {
+        x: 50.,
+        y: -20.,
+        size: Size { w: 100., h: 40. },
+    });
+    assert_eq!(
+        inner,
+        Bounds {
+            x: 50.,
+            y: 0.,
+            size: Size { w: 50., h: 20. },
+        }
+    );
+    let disjoint = clip.push(Bounds {
+        x: 200.,
+        y: 200.,
+        size: Size { w: 10., h: 10. },
+    });
+    assert_eq!(disjoint.size, Size { w: 0., h: 0. });
+
+    assert_eq!(clip.pop(), Some(inner));
+    assert_eq!(clip.pop(), Some(outer));
+    assert_eq!(clip.pop(), None);
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Size {
     pub w: f32,
@@ -58,6 +153,36 @@ pub struct Scale {
     pub y: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Padding {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Padding {
+    pub fn all(value: f32) -> Self {
+        Padding {
+            top: value,
+            right: value,
+            bottom: value,
+            left: value,
+        }
+    }
+
+    pub fn apply_to(&self, bounds: Bounds) -> Bounds {
+        Bounds {
+            x: bounds.x + self.left,
+            y: bounds.y + self.top,
+            size: Size {
+                w: (bounds.size.w - self.left - self.right).max(0.),
+                h: (bounds.size.h - self.top - self.bottom).max(0.),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Space {
     Fill,
@@ -125,6 +250,11 @@ pub fn next_id() -> Id {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Id(u32);
 
+impl Id {
+    /// Target of messages offered to every element, see `Message::broadcast`.
+    pub const BROADCAST: Id = Id(u32::MAX);
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum KeyState {
     /// Key was pressed this frame.
@@ -137,82 +267,521 @@ pub enum KeyState {
     Unpressed,
 }
 
-#[derive(Debug)]
+impl KeyState {
+    /// State for the next frame given whether the key is currently down.
+    pub fn advance(self, down: bool) -> KeyState {
+        match (self, down) {
+            (KeyState::Released | KeyState::Unpressed, true) => KeyState::Pressed,
+            (KeyState::Held | KeyState::Pressed, true) => KeyState::Held,
+            (KeyState::Released | KeyState::Unpressed, false) => KeyState::Unpressed,
+            (KeyState::Held | KeyState::Pressed, false) => KeyState::Released,
+        }
+    }
+}
+
+/// Modifier keys held down this frame, left and right variants combined.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+#[derive(Debug, Clone)]
 pub struct Key {
     pub key: KeyCode,
     pub state: KeyState,
 }
 
-#[derive(Debug)]
+/// Device independent navigation input, e.g. from a gamepad's d-pad and face buttons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NavigationAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+#[derive(Debug, Clone)]
 pub struct AppState {
     pub mouse_position: Position,
+    /// Mouse wheel movement this frame, positive `y` scrolls up.
+    pub wheel: Position,
     pub right_click: KeyState,
     pub left_click: KeyState,
-    pub input: Option<char>,
+    pub middle_click: KeyState,
+    /// Characters typed this frame, in order.
+    pub input: Vec<char>,
+    /// Every key that isn't `KeyState::Unpressed`.
     pub keys: Vec<Key>,
+    pub modifiers: Modifiers,
+    pub navigation: Option<NavigationAction>,
+    /// Topmost element under the pointer, see `Element::hit_test`. Only this element should
+    /// react to hovering and clicking.
+    pub hovered: Option<Id>,
     pub dt: f32,
 }
 
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            mouse_position: Position { x: 0., y: 0. },
+            wheel: Position { x: 0., y: 0. },
+            right_click: KeyState::Unpressed,
+            left_click: KeyState::Unpressed,
+            middle_click: KeyState::Unpressed,
+            input: vec![],
+            keys: vec![],
+            modifiers: Modifiers::default(),
+            navigation: None,
+            hovered: None,
+            dt: 0.,
+        }
+    }
+}
+
+impl AppState {
+    /// Whether `key` went down this frame.
+    pub fn is_pressed(&self, key: KeyCode) -> bool {
+        self.keys
+            .iter()
+            .any(|k| k.key == key && k.state == KeyState::Pressed)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Message {
     pub target: Id,
     pub data: MessageData,
 }
 
+impl Message {
+    /// Message carrying a payload defined outside of this crate, see `CustomMessage`.
+    pub fn custom<T: Any + fmt::Debug + Send + Sync>(target: Id, payload: T) -> Self {
+        Message {
+            target,
+            data: MessageData::Custom(CustomMessage::new(payload)),
+        }
+    }
+
+    /// Message offered to every element in the tree instead of a single target.
+    pub fn broadcast(data: MessageData) -> Self {
+        Message {
+            target: Id::BROADCAST,
+            data,
+        }
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.target == Id::BROADCAST
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MessageData {
     Button(ButtonMessage),
     ExpandableButton(ExpandableButtonMessage),
+    ScrollContainer(ScrollContainerMessage),
+    TextInput(TextInputMessage),
+    Focus(FocusMessage),
+    Custom(CustomMessage),
     Null,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    /// Travelling from the root down towards the target.
+    Capture,
+    /// Travelling from the target back up to the root.
+    Bubble,
+}
+
+/// A message on its way through the ancestors of its target, see `Router::dispatch`.
+#[derive(Debug)]
+pub struct Event<'a> {
+    pub msg: &'a Message,
+    pub phase: Phase,
+    /// Index of the child of the current element the event is passing through.
+    pub child: usize,
+    stopped: bool,
+}
+
+impl<'a> Event<'a> {
+    pub fn new(msg: &'a Message) -> Self {
+        Event {
+            msg,
+            phase: Phase::Capture,
+            child: 0,
+            stopped: false,
+        }
+    }
+
+    /// Keeps the event from reaching any further elements, including the target when called
+    /// during capture.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+impl MessageData {
+    /// The payload of a `MessageData::Custom` if it is a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match self {
+            MessageData::Custom(custom) => custom.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+trait Payload: Any + fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + fmt::Debug + Send + Sync> Payload for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Type-erased message payload, lets elements outside of this crate send their own message
+/// types through `UIContext` and `EventObserver`.
+#[derive(Clone)]
+pub struct CustomMessage(Arc<dyn Payload>);
+
+impl CustomMessage {
+    pub fn new<T: Any + fmt::Debug + Send + Sync>(payload: T) -> Self {
+        CustomMessage(Arc::new(payload))
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        (*self.0).as_any().is::<T>()
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        (*self.0).as_any().downcast_ref()
+    }
+}
+
+impl fmt::Debug for CustomMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[test]
+pub fn test_custom_message() {
+    #[derive(Debug, PartialEq)]
+    enum SliderMessage {
+        Moved(f32),
+    }
+
+    let ctx = UIContext::new();
+    let mut observer = EventObserver::new();
+    let slider = next_id();
+    let rx = observer.observe(slider);
+
+    ctx.tx
+        .send(Message::custom(slider, SliderMessage::Moved(0.5)))
+        .unwrap();
+    for msg in ctx.rx.drain() {
+        assert_eq!(format!("{:?}", msg.data), "Custom(Moved(0.5))");
+        observer.handle(msg);
+    }
+
+    let data = rx.try_recv().unwrap();
+    assert_eq!(
+        data.downcast_ref::<SliderMessage>(),
+        Some(&SliderMessage::Moved(0.5))
+    );
+    assert_eq!(data.downcast_ref::<String>(), None);
+}
+
 pub struct UIContext {
     pub rx: flume::Receiver<Message>,
     pub tx: flume::Sender<Message>,
+    /// Used by elements to size their text during layout.
+    pub measurer: Rc<dyn TextMeasurer>,
+    /// Passed to the callbacks of elements built with this context.
+    pub app: AppHandle,
+}
+
+impl Default for UIContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UIContext {
     pub fn new() -> Self {
+        Self::with_measurer(Rc::new(MacroquadTextMeasurer))
+    }
+
+    pub fn with_measurer(measurer: Rc<dyn TextMeasurer>) -> Self {
         let (tx, rx) = flume::unbounded();
-        UIContext { rx, tx }
+        UIContext {
+            rx,
+            tx,
+            measurer,
+            app: AppHandle::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Subscribers {
+    next_key: usize,
+    targets: HashMap<Id, Vec<(usize, flume::Sender<MessageData>)>>,
+    all: Vec<(usize, flume::Sender<Message>)>,
+}
+
+impl Subscribers {
+    fn remove(&mut self, target: Option<Id>, key: usize) {
+        match target {
+            Some(target) => {
+                if let Some(senders) = self.targets.get_mut(&target) {
+                    senders.retain(|(k, _)| *k != key);
+                    if senders.is_empty() {
+                        self.targets.remove(&target);
+                    }
+                }
+            }
+            None => self.all.retain(|(k, _)| *k != key),
+        }
     }
 }
 
+/// Hands out messages to subscribers outside the element tree.
+#[derive(Default)]
 pub struct EventObserver {
-    pub targets: HashMap<Id, flume::Sender<MessageData>>,
+    subscribers: Rc<RefCell<Subscribers>>,
+}
+
+/// Receives the messages of an `EventObserver` subscription until it is dropped.
+pub struct Subscription<T> {
+    rx: flume::Receiver<T>,
+    key: usize,
+    target: Option<Id>,
+    subscribers: Weak<RefCell<Subscribers>>,
+}
+
+impl<T> Subscription<T> {
+    /// Same as dropping the subscription.
+    pub fn unsubscribe(self) {}
+}
+
+impl<T> std::ops::Deref for Subscription<T> {
+    type Target = flume::Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rx
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.borrow_mut().remove(self.target, self.key);
+        }
+    }
 }
 
 impl EventObserver {
     pub fn new() -> Self {
-        EventObserver {
-            targets: HashMap::new(),
-        }
+        Self::default()
     }
 
-    pub fn observe(&mut self, target: Id) -> flume::Receiver<MessageData> {
+    fn subscribe<T>(
+        &self,
+        target: Option<Id>,
+        register: impl FnOnce(&mut Subscribers, usize, flume::Sender<T>),
+    ) -> Subscription<T> {
         let (tx, rx) = flume::unbounded();
-        self.targets.insert(target, tx);
-        rx
+        let mut subscribers = self.subscribers.borrow_mut();
+        let key = subscribers.next_key;
+        subscribers.next_key += 1;
+        register(&mut subscribers, key, tx);
+        Subscription {
+            rx,
+            key,
+            target,
+            subscribers: Rc::downgrade(&self.subscribers),
+        }
+    }
+
+    /// Subscribes to messages targeted at `target`. Any number of subscriptions can watch the
+    /// same target.
+    pub fn observe(&mut self, target: Id) -> Subscription<MessageData> {
+        self.subscribe(Some(target), |subscribers, key, tx| {
+            subscribers
+                .targets
+                .entry(target)
+                .or_default()
+                .push((key, tx))
+        })
+    }
+
+    /// Subscribes to every message, whatever its target.
+    pub fn observe_all(&mut self) -> Subscription<Message> {
+        self.subscribe(None, |subscribers, key, tx| subscribers.all.push((key, tx)))
+    }
+
+    /// Number of live subscriptions for `target`, not counting `observe_all` ones.
+    pub fn subscriber_count(&self, target: Id) -> usize {
+        self.subscribers
+            .borrow()
+            .targets
+            .get(&target)
+            .map_or(0, Vec::len)
     }
 
     pub fn handle(&self, msg: Message) {
-        for (&id, tx) in &self.targets {
-            if id == msg.target {
-                tx.send(msg.data).unwrap();
-                break;
+        let mut subscribers = self.subscribers.borrow_mut();
+        // Receivers dropped without going through `Subscription` are pruned on the way.
+        subscribers
+            .all
+            .retain(|(_, tx)| tx.send(msg.clone()).is_ok());
+        if let Some(senders) = subscribers.targets.get_mut(&msg.target) {
+            senders.retain(|(_, tx)| tx.send(msg.data.clone()).is_ok());
+            if senders.is_empty() {
+                subscribers.targets.remove(&msg.target);
             }
         }
     }
 }
 
+#[test]
+pub fn test_event_observer() {
+    let mut observer = EventObserver::new();
+    let target = next_id();
+    let msg = || Message {
+        target,
+        data: MessageData::Null,
+    };
+
+    let first = observer.observe(target);
+    let second = observer.observe(target);
+    let all = observer.observe_all();
+    observer.handle(msg());
+    observer.handle(Message {
+        target: next_id(),
+        data: MessageData::Null,
+    });
+    assert_eq!(first.drain().count(), 1);
+    assert_eq!(second.drain().count(), 1);
+    assert_eq!(all.drain().count(), 2);
+
+    second.unsubscribe();
+    assert_eq!(observer.subscriber_count(target), 1);
+    observer.handle(msg());
+    assert_eq!(first.drain().count(), 1);
+
+    drop(all);
+    drop(first);
+    assert_eq!(observer.subscriber_count(target), 0);
+    observer.handle(msg());
+}
+
+/// Font size used by the built-in elements.
+pub const DEFAULT_FONT_SIZE: u16 = 20;
+
+pub trait TextMeasurer {
+    /// Size `text` takes up when drawn with `Renderer::draw_text` using the same font and size.
+    fn measure_text(&self, text: &str, font: Option<Font>, font_size: u16) -> Size;
+}
+
 pub trait Renderer {
     fn draw_rectangle(&self, bounds: Bounds, texture: Option<u32>, color: Color);
-    fn draw_text(&self, bounds: Bounds, text: &str, color: Color);
+    /// Draws `text` with its baseline starting at the position of `bounds`.
+    fn draw_text(
+        &self,
+        bounds: Bounds,
+        text: &str,
+        font: Option<Font>,
+        font_size: u16,
+        color: Color,
+    );
+    fn draw_rectangle_lines(&self, bounds: Bounds, thickness: f32, color: Color);
+
+    /// Restricts all following draw calls to `bounds` until the matching `pop_clip_rect`.
+    /// Nested clip rects are intersected with the ones already pushed, so children can never
+    /// draw outside of what their ancestors allow.
+    fn push_clip_rect(&self, bounds: Bounds);
+    fn pop_clip_rect(&self);
+}
+
+/// Stack of nested clip rects, every entry is already intersected with the ones below it.
+#[derive(Debug, Default, Clone)]
+pub struct ClipStack {
+    stack: Vec<Bounds>,
 }
 
-pub trait Element {
+impl ClipStack {
+    /// Pushes `bounds` and returns the resulting clip rect.
+    pub fn push(&mut self, bounds: Bounds) -> Bounds {
+        let clip = match self.current() {
+            Some(current) => current.intersect(bounds),
+            None => bounds,
+        };
+        self.stack.push(clip);
+        clip
+    }
+
+    /// Pops the innermost clip rect and returns the one that applies again.
+    pub fn pop(&mut self) -> Option<Bounds> {
+        self.stack.pop();
+        self.current()
+    }
+
+    pub fn current(&self) -> Option<Bounds> {
+        self.stack.last().copied()
+    }
+}
+
+/// Upcasts to `dyn Element`, implemented for every element so provided methods of `Element`
+/// can hand out `self` as a trait object.
+pub trait AsElement {
+    fn as_element(&self) -> &dyn Element;
+    fn as_element_mut(&mut self) -> &mut dyn Element;
+}
+
+impl<T: Element> AsElement for T {
+    fn as_element(&self) -> &dyn Element {
+        self
+    }
+    fn as_element_mut(&mut self) -> &mut dyn Element {
+        self
+    }
+}
+
+fn walk<'a>(
+    element: &'a dyn Element,
+    path: &mut Vec<usize>,
+    visit: &mut dyn FnMut(&[usize], &'a dyn Element),
+) {
+    visit(path, element);
+    for (idx, child) in element.children().into_iter().enumerate() {
+        path.push(idx);
+        walk(child, path, visit);
+        path.pop();
+    }
+}
+
+pub trait Element: AsElement {
     fn update(&mut self, state: &AppState);
-    fn handle(&mut self, msg: &Message);
+    /// Called by `Router` for messages targeted at this element. Children are not handled by
+    /// their parent, see `capture` and `bubble` for observing messages sent to descendants.
+    fn handle(&mut self, _msg: &Message) {}
+    /// Called for messages targeted at a descendant before they reach it.
+    fn capture(&mut self, _event: &mut Event) {}
+    /// Called for messages targeted at a descendant after it handled them.
+    fn bubble(&mut self, _event: &mut Event) {}
     fn draw(&self, renderer: &dyn Renderer);
 
     fn set_bounds(&mut self, bounds: Bounds);
@@ -225,9 +794,311 @@ pub trait Element {
         self.write_all_bounds(&mut v);
         v
     }
+    /// Collects the elements that can receive keyboard focus, in tree order.
+    fn write_focusable(&self, _v: &mut Vec<Focusable>) {}
+
+    /// Whether the element would scroll if the mouse wheel moved by `wheel` at `position`,
+    /// so that enclosing scrollables leave the wheel to it.
+    fn can_scroll(&self, _position: Position, _wheel: Position) -> bool {
+        false
+    }
 
     fn min_size(&self) -> Size;
     fn space(&self) -> Space;
+
+    fn id(&self) -> Id;
+    /// Name of the concrete type, for debugging.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+    /// Direct children, in draw order.
+    fn children(&self) -> Vec<&dyn Element> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        Vec::new()
+    }
+
+    /// Draws content that has to appear above the whole tree, like dropdowns and tooltips. It
+    /// is called for the entire tree after `draw`, see `draw_with_overlays`. `visible` is the
+    /// area the element can be seen through, children entirely outside of it are skipped.
+    fn draw_overlay(&self, renderer: &dyn Renderer, visible: Bounds) {
+        for child in self.children() {
+            if child.bounds().overlaps(visible) {
+                child.draw_overlay(renderer, visible);
+            }
+        }
+    }
+    /// Like `hit_test`, for the content drawn by `draw_overlay`.
+    fn hit_test_overlay(&self, position: Position, visible: Bounds) -> Option<Id> {
+        self.children()
+            .into_iter()
+            .rev()
+            .filter(|child| child.bounds().overlaps(visible))
+            .find_map(|child| child.hit_test_overlay(position, visible))
+    }
+
+    /// Whether this element or anything below it changed in a way that affects layout, like
+    /// its `min_size`. The tree is laid out again from the root when it does, after which
+    /// `set_bounds` clears the flag.
+    fn needs_layout(&self) -> bool {
+        self.children().iter().any(|child| child.needs_layout())
+    }
+
+    /// Elements that only arrange their children, like containers, let the pointer through to
+    /// whatever is below them.
+    fn pointer_transparent(&self) -> bool {
+        false
+    }
+    /// The topmost element at `position`. Children are drawn after their parent and later
+    /// children after earlier ones, so they are tried in reverse before the element itself.
+    fn hit_test(&self, position: Position) -> Option<Id> {
+        self.children()
+            .into_iter()
+            .rev()
+            .find_map(|child| child.hit_test(position))
+            .or_else(|| {
+                (!self.pointer_transparent() && self.bounds().contains(position)).then(|| self.id())
+            })
+    }
+
+    /// This element or the first descendant with the given id, searching depth-first.
+    fn find(&self, id: Id) -> Option<&dyn Element> {
+        if self.id() == id {
+            return Some(self.as_element());
+        }
+        self.children().into_iter().find_map(|child| child.find(id))
+    }
+    fn find_mut(&mut self, id: Id) -> Option<&mut dyn Element> {
+        if self.id() == id {
+            return Some(self.as_element_mut());
+        }
+        self.children_mut()
+            .into_iter()
+            .find_map(|child| child.find_mut(id))
+    }
+    /// The descendant at `path`, a list of child indices as returned by `children`. An empty
+    /// path is the element itself.
+    fn descendant(&self, path: &[usize]) -> Option<&dyn Element> {
+        match path.split_first() {
+            Some((&first, rest)) => self.children().into_iter().nth(first)?.descendant(rest),
+            None => Some(self.as_element()),
+        }
+    }
+    fn descendant_mut(&mut self, path: &[usize]) -> Option<&mut dyn Element> {
+        match path.split_first() {
+            Some((&first, rest)) => self
+                .children_mut()
+                .into_iter()
+                .nth(first)?
+                .descendant_mut(rest),
+            None => Some(self.as_element_mut()),
+        }
+    }
+    /// Visits this element and all of its descendants depth-first, parents before their
+    /// children, together with their path.
+    fn walk(&self, visit: &mut dyn FnMut(&[usize], &dyn Element)) {
+        walk(self.as_element(), &mut Vec::new(), visit);
+    }
+
+    /// Boxes the element so it can be mixed with other element types, e.g. as the children of
+    /// a `VListContainer<Box<dyn Element>>`.
+    fn boxed(self) -> Box<dyn Element>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+// Forwards every method, including the provided ones, so overrides of the pointee are kept.
+macro_rules! forward_element {
+    ($ty:ty) => {
+        impl<E: Element + ?Sized> Element for $ty {
+            fn update(&mut self, state: &AppState) {
+                (**self).update(state)
+            }
+            fn handle(&mut self, msg: &Message) {
+                (**self).handle(msg)
+            }
+            fn capture(&mut self, event: &mut Event) {
+                (**self).capture(event)
+            }
+            fn bubble(&mut self, event: &mut Event) {
+                (**self).bubble(event)
+            }
+            fn draw(&self, renderer: &dyn Renderer) {
+                (**self).draw(renderer)
+            }
+            fn set_bounds(&mut self, bounds: Bounds) {
+                (**self).set_bounds(bounds)
+            }
+            fn bounds(&self) -> Bounds {
+                (**self).bounds()
+            }
+            fn write_all_bounds(&self, v: &mut Vec<Bounds>) {
+                (**self).write_all_bounds(v)
+            }
+            fn all_bounds(&self) -> Vec<Bounds> {
+                (**self).all_bounds()
+            }
+            fn write_focusable(&self, v: &mut Vec<Focusable>) {
+                (**self).write_focusable(v)
+            }
+            fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+                (**self).can_scroll(position, wheel)
+            }
+            fn min_size(&self) -> Size {
+                (**self).min_size()
+            }
+            fn space(&self) -> Space {
+                (**self).space()
+            }
+            fn id(&self) -> Id {
+                (**self).id()
+            }
+            fn type_name(&self) -> &'static str {
+                (**self).type_name()
+            }
+            fn children(&self) -> Vec<&dyn Element> {
+                (**self).children()
+            }
+            fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+                (**self).children_mut()
+            }
+            fn draw_overlay(&self, renderer: &dyn Renderer, visible: Bounds) {
+                (**self).draw_overlay(renderer, visible)
+            }
+            fn hit_test_overlay(&self, position: Position, visible: Bounds) -> Option<Id> {
+                (**self).hit_test_overlay(position, visible)
+            }
+            fn needs_layout(&self) -> bool {
+                (**self).needs_layout()
+            }
+            fn pointer_transparent(&self) -> bool {
+                (**self).pointer_transparent()
+            }
+            fn hit_test(&self, position: Position) -> Option<Id> {
+                (**self).hit_test(position)
+            }
+            fn find(&self, id: Id) -> Option<&dyn Element> {
+                (**self).find(id)
+            }
+            fn find_mut(&mut self, id: Id) -> Option<&mut dyn Element> {
+                (**self).find_mut(id)
+            }
+            fn descendant(&self, path: &[usize]) -> Option<&dyn Element> {
+                (**self).descendant(path)
+            }
+            fn descendant_mut(&mut self, path: &[usize]) -> Option<&mut dyn Element> {
+                (**self).descendant_mut(path)
+            }
+            fn walk(&self, visit: &mut dyn FnMut(&[usize], &dyn Element)) {
+                (**self).walk(visit)
+            }
+        }
+    };
+}
+
+forward_element!(Box<E>);
+forward_element!(&mut E);
+
+#[test]
+pub fn test_boxed_elements() {
+    use crate::button::{Button, ButtonProps};
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::scroll_container::{ScrollContainer, ScrollContainerProps};
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let button = Button::new(ButtonProps::default(), &ctx);
+    let scroll = ScrollContainer::new(
+        ScrollContainerProps::new(Button::new(ButtonProps::default(), &ctx)),
+        &ctx,
+    );
+    let ids = [button.id(), scroll.id()];
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![button.boxed(), scroll.boxed()],
+        ..Default::default()
+    });
+    root.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 100. },
+    });
+
+    let children: Vec<_> = root.children().iter().map(|child| child.id()).collect();
+    assert_eq!(children, ids);
+    // The list, both children and the button inside the scroll container.
+    assert_eq!(root.all_bounds().len(), 4);
+    let mut focusables = Vec::new();
+    root.write_focusable(&mut focusables);
+    assert_eq!(focusables.len(), 2);
+
+    // Borrowed elements can be passed wherever an element is taken by value.
+    fn resize(mut element: impl Element) {
+        element.set_bounds(Bounds {
+            x: 0.,
+            y: 0.,
+            size: Size { w: 50., h: 50. },
+        });
+    }
+    resize(&mut root);
+    assert_eq!(root.bounds().size, Size { w: 50., h: 50. });
+}
+
+#[test]
+pub fn test_tree_traversal() {
+    use crate::button::{Button, ButtonProps};
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::vlist_container::{VListContainer, VListContainerProps};
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let list = |n| {
+        VListContainer::new(VListContainerProps {
+            children: (0..n)
+                .map(|_| Button::new(ButtonProps::default(), &ctx))
+                .collect(),
+            ..Default::default()
+        })
+    };
+    let mut root = VListContainer::new(VListContainerProps {
+        children: vec![list(1), list(2)],
+        ..Default::default()
+    });
+    let target = root.children[1].children[1].id;
+
+    let mut visited = Vec::new();
+    root.walk(&mut |path, element| visited.push((path.to_vec(), element.id())));
+    assert_eq!(visited.len(), 6);
+    assert_eq!(visited[0], (vec![], root.id));
+    assert_eq!(visited[5], (vec![1, 1], target));
+
+    assert_eq!(root.find(target).map(|e| e.id()), Some(target));
+    assert_eq!(root.descendant(&[1, 1]).map(|e| e.id()), Some(target));
+    assert!(root.descendant(&[0, 1]).is_none());
+    assert!(root.find(next_id()).is_none());
+
+    root.find_mut(target).unwrap().set_bounds(Bounds {
+        x: 1.,
+        y: 2.,
+        size: Size { w: 3., h: 4. },
+    });
+    assert_ne!(root.children[1].children[1].outer_bounds.size.w, 0.);
+    let element = root.descendant_mut(&[1]).unwrap();
+    assert_eq!(element.children().len(), 2);
+}
+
+/// Draws `root` followed by its overlay layer.
+pub fn draw_with_overlays(root: &dyn Element, renderer: &dyn Renderer) {
+    root.draw(renderer);
+    root.draw_overlay(renderer, root.bounds());
+}
+
+/// Topmost element at `position`, looking at the overlay layer before the rest of the tree.
+pub fn hit_test_with_overlays(root: &dyn Element, position: Position) -> Option<Id> {
+    root.hit_test_overlay(position, root.bounds())
+        .or_else(|| root.hit_test(position))
 }
 
 /*pub trait ElementBase {
@@ -293,12 +1164,20 @@ impl<B: ElementBase> Element for BasicElement<B> {
 
 pub struct MacroquadRenderer {
     textures: Vec<Texture2D>,
+    clip_stack: RefCell<ClipStack>,
+}
+
+impl Default for MacroquadRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MacroquadRenderer {
     pub fn new() -> Self {
         MacroquadRenderer {
             textures: Vec::new(),
+            clip_stack: RefCell::new(ClipStack::default()),
         }
     }
 
@@ -306,6 +1185,18 @@ impl MacroquadRenderer {
         self.textures.push(tex);
         self.textures.len() as u32 - 1
     }
+
+    fn apply_clip(&self, clip: Option<Bounds>) {
+        let gl = unsafe { get_internal_gl() };
+        gl.quad_gl.scissor(clip.map(|clip| {
+            (
+                clip.x as i32,
+                clip.y as i32,
+                clip.size.w as i32,
+                clip.size.h as i32,
+            )
+        }));
+    }
 }
 
 impl Renderer for MacroquadRenderer {
@@ -326,17 +1217,60 @@ impl Renderer for MacroquadRenderer {
         }
     }
 
-    fn draw_text(&self, bounds: Bounds, text: &str, color: Color) {
+    fn draw_text(
+        &self,
+        bounds: Bounds,
+        text: &str,
+        font: Option<Font>,
+        font_size: u16,
+        color: Color,
+    ) {
         draw_text_ex(
             text,
             bounds.x,
             bounds.y,
             TextParams {
+                font: font.unwrap_or_default(),
+                font_size,
                 color,
                 ..Default::default()
             },
         );
     }
+
+    fn draw_rectangle_lines(&self, bounds: Bounds, thickness: f32, color: Color) {
+        draw_rectangle_lines(
+            bounds.x,
+            bounds.y,
+            bounds.size.w,
+            bounds.size.h,
+            thickness,
+            color,
+        );
+    }
+
+    fn push_clip_rect(&self, bounds: Bounds) {
+        let clip = self.clip_stack.borrow_mut().push(bounds);
+        self.apply_clip(Some(clip));
+    }
+
+    fn pop_clip_rect(&self) {
+        let clip = self.clip_stack.borrow_mut().pop();
+        self.apply_clip(clip);
+    }
+}
+
+/// Measures text with macroquad's fonts, needs a running macroquad context.
+pub struct MacroquadTextMeasurer;
+
+impl TextMeasurer for MacroquadTextMeasurer {
+    fn measure_text(&self, text: &str, font: Option<Font>, font_size: u16) -> Size {
+        let dim = measure_text(text, font, font_size, 1.0);
+        Size {
+            w: dim.width,
+            h: dim.height,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,4 +1,8 @@
-use crate::types::{next_id, AppState, Bounds, Element, Id, Message, Renderer, Size, Space};
+use crate::{
+    children::KeyedChildren,
+    focus::Focusable,
+    types::{next_id, AppState, Bounds, Element, Id, Position, Renderer, Size, Space},
+};
 
 pub struct VListContainerProps<E> {
     pub id: Id,
@@ -24,6 +28,9 @@ pub struct VListContainer<E> {
     pub children: Vec<E>,
     pub space: Space,
     pub spacing: f32,
+
+    // Children changed since the last layout.
+    dirty: bool,
 }
 
 impl<E> VListContainer<E> {
@@ -38,17 +45,28 @@ impl<E> VListContainer<E> {
             children: props.children,
             space: props.space,
             spacing: props.spacing,
+            dirty: false,
         }
     }
 }
 
-impl<E: Element> Element for VListContainer<E> {
-    fn handle(&mut self, msg: &Message) {
-        for child in &mut self.children {
-            child.handle(msg);
-        }
+impl<E: Element> KeyedChildren for VListContainer<E> {
+    type Entry = E;
+
+    fn entries(&self) -> &[E] {
+        &self.children
     }
 
+    fn entries_mut(&mut self) -> &mut Vec<E> {
+        &mut self.children
+    }
+
+    fn children_changed(&mut self) {
+        self.dirty = true;
+    }
+}
+
+impl<E: Element> Element for VListContainer<E> {
     fn update(&mut self, state: &AppState) {
         for child in &mut self.children {
             child.update(state);
@@ -62,6 +80,7 @@ impl<E: Element> Element for VListContainer<E> {
     }
 
     fn set_bounds(&mut self, bounds: Bounds) {
+        self.dirty = false;
         let mut min_size = Size { w: 0., h: 0. };
         let mut child_min_size = 0.;
         let mut fill_count = 0;
@@ -80,7 +99,7 @@ impl<E: Element> Element for VListContainer<E> {
                 }
             }
         }
-        let total_padding = self.spacing * (self.children.len() - 1) as f32;
+        let total_padding = self.spacing * self.children.len().saturating_sub(1) as f32;
         min_size.h += total_padding;
 
         let size = match self.space {
@@ -124,19 +143,50 @@ impl<E: Element> Element for VListContainer<E> {
     }
 
     fn min_size(&self) -> Size {
-        self.children.iter().map(|child| child.min_size()).fold(
+        let total_padding = self.spacing * self.children.len().saturating_sub(1) as f32;
+        let min_size = self.children.iter().map(|child| child.min_size()).fold(
             Size { w: 0., h: 0. },
             |acc, child| Size {
                 w: acc.w.max(child.w),
                 h: acc.h + child.h,
             },
-        )
+        );
+        Size {
+            w: min_size.w,
+            h: min_size.h + total_padding,
+        }
     }
 
     fn space(&self) -> Space {
         self.space
     }
 
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn pointer_transparent(&self) -> bool {
+        true
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.dirty || self.children.iter().any(|child| child.needs_layout())
+    }
+
+    fn children(&self) -> Vec<&dyn Element> {
+        self.children
+            .iter()
+            .map(|child| child as &dyn Element)
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Element> {
+        self.children
+            .iter_mut()
+            .map(|child| child as &mut dyn Element)
+            .collect()
+    }
+
     fn bounds(&self) -> Bounds {
         self.bounds
     }
@@ -147,4 +197,83 @@ impl<E: Element> Element for VListContainer<E> {
             child.write_all_bounds(v)
         }
     }
+
+    fn can_scroll(&self, position: Position, wheel: Position) -> bool {
+        self.children
+            .iter()
+            .any(|child| child.can_scroll(position, wheel))
+    }
+
+    fn write_focusable(&self, v: &mut Vec<Focusable>) {
+        for child in &self.children {
+            child.write_focusable(v)
+        }
+    }
+}
+
+#[test]
+pub fn test_vlist_children_changes() {
+    use crate::button::{Button, ButtonProps};
+    use crate::recording_renderer::MonospaceTextMeasurer;
+    use crate::types::{Anchor, AnchorX, AnchorY, UIContext};
+    use std::rc::Rc;
+
+    let ctx = UIContext::with_measurer(Rc::new(MonospaceTextMeasurer));
+    let button = || {
+        Button::new(
+            ButtonProps {
+                anchor: Anchor {
+                    x: AnchorX::Left,
+                    y: AnchorY::Top,
+                },
+                ..Default::default()
+            },
+            &ctx,
+        )
+    };
+    let mut list = VListContainer::new(VListContainerProps {
+        children: vec![button()],
+        spacing: 0.,
+        ..Default::default()
+    });
+    list.set_bounds(Bounds {
+        x: 0.,
+        y: 0.,
+        size: Size { w: 100., h: 100. },
+    });
+    let first = list.children[0].id;
+
+    let layout = |list: &mut VListContainer<Button>| {
+        assert!(list.needs_layout());
+        list.set_bounds(list.bounds);
+        assert!(!list.needs_layout());
+    };
+
+    // Children added at runtime are laid out by the next layout pass.
+    list.push(button());
+    let second = list.children[1].id;
+    layout(&mut list);
+    assert_eq!(list.children[1].outer_bounds.y, 50.);
+
+    assert!(list.move_to(second, 0));
+    layout(&mut list);
+    assert_eq!(list.children[0].id, second);
+    assert_eq!(list.children[1].outer_bounds.y, 50.);
+
+    assert_eq!(list.remove(first).map(|button| button.id), Some(first));
+    assert!(list.remove(first).is_none());
+    layout(&mut list);
+    assert_eq!(list.children[0].outer_bounds.size.h, 100.);
+
+    // Indices past the end insert at the end.
+    list.insert(5, button());
+    assert_eq!(list.children[0].id, second);
+    assert_eq!(list.children.len(), 2);
+    layout(&mut list);
+
+    list.children[0].set_text("Changed");
+    assert!(list.needs_layout());
+
+    list.clear();
+    assert!(!list.move_to(second, 0));
 }